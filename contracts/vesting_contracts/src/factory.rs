@@ -1,7 +1,10 @@
 use soroban_sdk::{
-    contract, contractimpl, contractmeta, contracttype, Address, BytesN, Env, Map, Vec,
+    contract, contractimpl, contractmeta, contracttype, token, xdr::ToXdr, Address, Bytes,
+    BytesN, Env, IntoVal, String, Symbol, TryFromVal, Val, Vec,
 };
 
+use crate::{InitConfig, Policy, VestingContractClient};
+
 // Contract metadata for the factory
 contractmeta!(
     key = "Description",
@@ -11,60 +14,337 @@ contractmeta!(
 #[contract]
 pub struct VestingFactory;
 
+/// Registry entries are bumped to about 120 days whenever they drop below about 30 days
+const REGISTRY_TTL_THRESHOLD: u32 = 17_280 * 30;
+const REGISTRY_TTL_EXTEND_TO: u32 = 17_280 * 120;
+
+// The deployment registry grows with every child, so it lives in persistent storage with
+// one entry per child and per index position, rather than in lists that every deployment
+// has to load and rewrite in full.
 #[contracttype]
 enum DataKey {
-    DeployedContracts,
     WasmHash,
+    Owner,
+    WasmHashHistory,      // every template hash in order; the last one is current
+    WhitelistedTokens,    // Vec<Address> of tokens children may be deployed for
+    Whitelisted(Address), // per-token flag, so checks don't read the whole list
+    Deployment(Address),  // DeploymentRecord of a child contract
+    DeploymentCount,
+    DeployedContract(u32), // child contracts in deployment order
+    AdminDeploymentCount(Address),
+    AdminDeployment(Address, u32),
+    TokenDeploymentCount(Address),
+    TokenDeployment(Address, u32),
+}
+
+/// Registry entry for a contract deployed by the factory
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeploymentRecord {
+    pub contract: Address,
+    pub org_name: String,
+    pub admin: Address,
+    pub token: Address,
+    /// 1-based index into the WASM hash history of the code the contract runs
+    pub wasm_version: u32,
+    pub created_at: u64,
 }
 
 #[contractimpl]
+#[allow(deprecated)]
 impl VestingFactory {
-    /// Initialize the factory with the WASM hash of the vesting contract
-    pub fn initialize_factory(env: Env, wasm_hash: BytesN<32>) {
+    /// Initialize the factory with its owner and the WASM hash of the vesting contract
+    pub fn initialize_factory(env: Env, owner: Address, wasm_hash: BytesN<32>) {
+        if env.storage().instance().has(&DataKey::Owner) {
+            panic!("Factory already initialized");
+        }
+        owner.require_auth();
+        env.storage().instance().set(&DataKey::Owner, &owner);
+
         // Store the WASM hash for future deployments
         env.storage().instance().set(&DataKey::WasmHash, &wasm_hash);
-
-        // Initialize the deployed contracts list
-        let deployed_contracts: Vec<Address> = Vec::new(&env);
+        let mut history: Vec<BytesN<32>> = Vec::new(&env);
+        history.push_back(wasm_hash);
         env.storage()
             .instance()
-            .set(&DataKey::DeployedContracts, &deployed_contracts);
+            .set(&DataKey::WasmHashHistory, &history);
     }
 
-    /// Deploy a new vesting contract for an organization
+    /// Deploy and initialize a new vesting contract for an organization.
+    /// The address is derived from `admin` and `org_name`, so each pair deploys once.
+    /// `initial_supply` is transferred from `admin` to the new contract.
+    /// With `allow_factory_upgrades` the admin opts in to `rollout_upgrade`.
     /// Only allows deployment if token is whitelisted
     pub fn deploy_new_vault_contract(
         env: Env,
+        org_name: String,
         admin: Address,
         initial_supply: i128,
         token: Address,
+        allow_factory_upgrades: bool,
     ) -> Address {
-        let _wasm_hash: BytesN<32> = env
+        admin.require_auth();
+
+        let wasm_hash: BytesN<32> = env
             .storage()
             .instance()
             .get(&DataKey::WasmHash)
             .unwrap_or_else(|| panic!("Factory not initialized - WASM hash not set"));
 
         // Check token whitelist
-        let whitelist: Map<Address, bool> = env
+        if !env
             .storage()
             .instance()
-            .get(&crate::WhitelistDataKey::WhitelistedTokens)
-            .unwrap_or(Map::new(&env));
-        if !whitelist.get(token.clone()).unwrap_or(false) {
+            .has(&DataKey::Whitelisted(token.clone()))
+        {
             panic!("Token not whitelisted");
         }
 
-        let _ = (admin, initial_supply);
-        panic!("Factory deployment is not implemented for this soroban-sdk version");
+        if initial_supply < 0 {
+            panic!("Initial supply must be non-negative");
+        }
+
+        let salt = Self::deployment_salt(&env, &org_name, &admin);
+        let contract_address = env
+            .deployer()
+            .with_current_contract(salt)
+            .deploy_v2(wasm_hash, ());
+
+        // Fund before initializing so the child's initial supply is actually held
+        if initial_supply > 0 {
+            token::Client::new(&env, &token).transfer(&admin, &contract_address, &initial_supply);
+        }
+        let child = VestingContractClient::new(&env, &contract_address);
+        child.initialize(&InitConfig {
+            admin: admin.clone(),
+            token: token.clone(),
+            initial_supply,
+            whitelist: Vec::new(&env),
+            policy: Policy::default(),
+        });
+        if allow_factory_upgrades {
+            child.set_upgrade_authority(&Some(env.current_contract_address()));
+        }
+
+        let record = DeploymentRecord {
+            contract: contract_address.clone(),
+            org_name: org_name.clone(),
+            admin: admin.clone(),
+            token: token.clone(),
+            wasm_version: Self::get_wasm_hash_history(env.clone()).len(),
+            created_at: env.ledger().timestamp(),
+        };
+        Self::write_registry(&env, &DataKey::Deployment(contract_address.clone()), &record);
+        Self::append_index(
+            &env,
+            DataKey::DeploymentCount,
+            DataKey::DeployedContract,
+            &contract_address,
+        );
+        Self::append_index(
+            &env,
+            DataKey::AdminDeploymentCount(admin.clone()),
+            |i| DataKey::AdminDeployment(admin.clone(), i),
+            &contract_address,
+        );
+        Self::append_index(
+            &env,
+            DataKey::TokenDeploymentCount(token.clone()),
+            |i| DataKey::TokenDeployment(token.clone(), i),
+            &contract_address,
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "VaultContractDeployed"), contract_address.clone()),
+            (org_name, admin, token),
+        );
+
+        contract_address
     }
 
-    /// Get all deployed contract addresses
-    pub fn get_deployed_contracts(env: Env) -> Vec<Address> {
+    /// Address a deployment for `org_name` and `admin` will have
+    pub fn get_deployment_address(env: Env, org_name: String, admin: Address) -> Address {
+        let salt = Self::deployment_salt(&env, &org_name, &admin);
+        env.deployer().with_current_contract(salt).deployed_address()
+    }
+
+    fn deployment_salt(env: &Env, org_name: &String, admin: &Address) -> BytesN<32> {
+        let mut data = Bytes::new(env);
+        data.append(&admin.clone().to_xdr(env));
+        data.append(&org_name.clone().to_xdr(env));
+        env.crypto().sha256(&data).to_bytes()
+    }
+
+    /// Number of contracts the factory has deployed
+    pub fn get_deployment_count(env: Env) -> u32 {
+        Self::read_registry(&env, &DataKey::DeploymentCount).unwrap_or(0)
+    }
+
+    /// Get up to `limit` deployed contract addresses in deployment order, starting at `start`
+    pub fn get_deployed_contracts(env: Env, start: u32, limit: u32) -> Vec<Address> {
+        Self::load_page(
+            &env,
+            DataKey::DeploymentCount,
+            DataKey::DeployedContract,
+            start,
+            limit,
+        )
+    }
+
+    /// Upgrade deployed contracts to `wasm_hash`, which must be in the WASM hash history
+    /// (only callable by factory owner). Contracts that are not in the registry or whose
+    /// admin has not opted in to factory upgrades are skipped; returns the upgraded ones.
+    pub fn rollout_upgrade(
+        env: Env,
+        contracts: Vec<Address>,
+        wasm_hash: BytesN<32>,
+    ) -> Vec<Address> {
+        Self::require_owner(&env);
+
+        let version = Self::get_wasm_hash_history(env.clone())
+            .last_index_of(&wasm_hash)
+            .unwrap_or_else(|| panic!("Unknown WASM hash"))
+            + 1;
+
+        let factory = env.current_contract_address();
+        let mut upgraded = Vec::new(&env);
+        for contract in contracts.iter() {
+            let Some(mut record) = Self::get_deployment(env.clone(), contract.clone()) else {
+                continue;
+            };
+            let child = VestingContractClient::new(&env, &contract);
+            if child.try_upgrade(&factory, &wasm_hash).is_err() {
+                continue;
+            }
+
+            record.wasm_version = version;
+            Self::write_registry(&env, &DataKey::Deployment(contract.clone()), &record);
+            upgraded.push_back(contract.clone());
+
+            env.events().publish(
+                (Symbol::new(&env, "ContractUpgraded"), contract),
+                (wasm_hash.clone(), version),
+            );
+        }
+
+        upgraded
+    }
+
+    /// Get the registry record of a deployed contract
+    pub fn get_deployment(env: Env, contract: Address) -> Option<DeploymentRecord> {
+        Self::read_registry(&env, &DataKey::Deployment(contract))
+    }
+
+    /// Get the contract deployed for `org_name` by `admin`, if any
+    pub fn get_deployment_by_org(
+        env: Env,
+        org_name: String,
+        admin: Address,
+    ) -> Option<DeploymentRecord> {
+        let contract = Self::get_deployment_address(env.clone(), org_name, admin);
+        Self::get_deployment(env, contract)
+    }
+
+    /// Get up to `limit` contracts deployed with `admin` as their admin, starting at `start`
+    pub fn get_deployments_by_admin(
+        env: Env,
+        admin: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<DeploymentRecord> {
+        let contracts = Self::load_page(
+            &env,
+            DataKey::AdminDeploymentCount(admin.clone()),
+            |i| DataKey::AdminDeployment(admin.clone(), i),
+            start,
+            limit,
+        );
+        Self::load_records(&env, contracts)
+    }
+
+    /// Get up to `limit` contracts deployed for `token`, starting at `start`
+    pub fn get_deployments_by_token(
+        env: Env,
+        token: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<DeploymentRecord> {
+        let contracts = Self::load_page(
+            &env,
+            DataKey::TokenDeploymentCount(token.clone()),
+            |i| DataKey::TokenDeployment(token.clone(), i),
+            start,
+            limit,
+        );
+        Self::load_records(&env, contracts)
+    }
+
+    /// Get up to `limit` deployment records in deployment order, starting at `start`
+    pub fn get_deployments(env: Env, start: u32, limit: u32) -> Vec<DeploymentRecord> {
+        let contracts = Self::get_deployed_contracts(env.clone(), start, limit);
+        Self::load_records(&env, contracts)
+    }
+
+    /// Store a registry entry and keep it alive
+    fn write_registry<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        env.storage().persistent().set(key, value);
         env.storage()
-            .instance()
-            .get(&DataKey::DeployedContracts)
-            .unwrap_or_else(|| Vec::new(&env))
+            .persistent()
+            .extend_ttl(key, REGISTRY_TTL_THRESHOLD, REGISTRY_TTL_EXTEND_TO);
+    }
+
+    /// Read a registry entry, extending its TTL if it exists
+    fn read_registry<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+        let value = env.storage().persistent().get(key);
+        if value.is_some() {
+            env.storage().persistent().extend_ttl(
+                key,
+                REGISTRY_TTL_THRESHOLD,
+                REGISTRY_TTL_EXTEND_TO,
+            );
+        }
+        value
+    }
+
+    /// Append `contract` to the index whose length is stored under `count_key`
+    fn append_index(
+        env: &Env,
+        count_key: DataKey,
+        entry_key: impl Fn(u32) -> DataKey,
+        contract: &Address,
+    ) {
+        let count: u32 = Self::read_registry(env, &count_key).unwrap_or(0);
+        Self::write_registry(env, &entry_key(count), contract);
+        Self::write_registry(env, &count_key, &(count + 1));
+    }
+
+    /// Read positions `start..start + limit` of an index, clamped to its length
+    fn load_page(
+        env: &Env,
+        count_key: DataKey,
+        entry_key: impl Fn(u32) -> DataKey,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Address> {
+        let count: u32 = Self::read_registry(env, &count_key).unwrap_or(0);
+        let end = start.saturating_add(limit).min(count);
+        let mut contracts = Vec::new(env);
+        for i in start..end {
+            if let Some(contract) = Self::read_registry(env, &entry_key(i)) {
+                contracts.push_back(contract);
+            }
+        }
+        contracts
+    }
+
+    fn load_records(env: &Env, contracts: Vec<Address>) -> Vec<DeploymentRecord> {
+        let mut records = Vec::new(env);
+        for contract in contracts.iter() {
+            if let Some(record) = Self::get_deployment(env.clone(), contract) {
+                records.push_back(record);
+            }
+        }
+        records
     }
 
     /// Get the WASM hash stored in the factory
@@ -72,13 +352,97 @@ impl VestingFactory {
         env.storage().instance().get(&DataKey::WasmHash)
     }
 
-    /// Update the WASM hash (only callable by factory owner/admin)
+    /// Update the WASM hash (only callable by factory owner)
     pub fn update_wasm_hash(env: Env, new_wasm_hash: BytesN<32>) {
-        // In a real implementation, you'd want admin access control here
-        // For now, we'll just update it directly
+        Self::require_owner(&env);
         env.storage()
             .instance()
             .set(&DataKey::WasmHash, &new_wasm_hash);
+
+        let mut history = Self::get_wasm_hash_history(env.clone());
+        history.push_back(new_wasm_hash.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::WasmHashHistory, &history);
+
+        env.events().publish(
+            (Symbol::new(&env, "WasmHashUpdated"),),
+            (new_wasm_hash, history.len()),
+        );
+    }
+
+    /// Every WASM hash the factory has used, oldest first; the last entry is current
+    pub fn get_wasm_hash_history(env: Env) -> Vec<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get(&DataKey::WasmHashHistory)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Get the factory owner
+    pub fn get_factory_owner(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Owner)
+            .unwrap_or_else(|| panic!("Factory not initialized"))
+    }
+
+    /// Hand the factory over to a new owner (only callable by factory owner)
+    pub fn transfer_factory_ownership(env: Env, new_owner: Address) {
+        Self::require_owner(&env);
+        new_owner.require_auth();
+        env.storage().instance().set(&DataKey::Owner, &new_owner);
+    }
+
+    /// Allow children to be deployed for `token` (only callable by factory owner)
+    pub fn whitelist_token(env: Env, token: Address) {
+        Self::require_owner(&env);
+        let key = DataKey::Whitelisted(token.clone());
+        if env.storage().instance().has(&key) {
+            return;
+        }
+        env.storage().instance().set(&key, &true);
+
+        let mut tokens = Self::get_factory_whitelist(env.clone());
+        tokens.push_back(token);
+        env.storage()
+            .instance()
+            .set(&DataKey::WhitelistedTokens, &tokens);
+    }
+
+    /// Stop new deployments for `token` (only callable by factory owner)
+    pub fn unwhitelist_token(env: Env, token: Address) {
+        Self::require_owner(&env);
+        let key = DataKey::Whitelisted(token.clone());
+        if !env.storage().instance().has(&key) {
+            panic!("Token not whitelisted");
+        }
+        env.storage().instance().remove(&key);
+
+        let mut tokens = Self::get_factory_whitelist(env.clone());
+        if let Some(index) = tokens.first_index_of(&token) {
+            tokens.remove(index);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::WhitelistedTokens, &tokens);
+    }
+
+    /// Tokens children may currently be deployed for
+    pub fn get_factory_whitelist(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::WhitelistedTokens)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    fn require_owner(env: &Env) {
+        let owner: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Owner)
+            .unwrap_or_else(|| panic!("Factory not initialized"));
+        owner.require_auth();
     }
 }
 
@@ -1,18 +1,41 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, token, vec, Address, Env, IntoVal, Map, Symbol, Vec, String};
-
+#![allow(clippy::too_many_arguments)]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, token, vec, Address, Env, IntoVal, Map, String, Symbol,
-    Vec,
+    contract, contractimpl, contracttype, token, Address, BytesN, Env, Map, String, Symbol,
+    TryFromVal, Val, Vec,
 };
 
-// 10 years in seconds (Issue #44)
+// 10 years in seconds (Issue #44); default for Policy::max_duration
 pub const MAX_DURATION: u64 = 315_360_000;
 
+// Default window after creation during which a vault can be clawed back
+pub const DEFAULT_CLAWBACK_GRACE_PERIOD: u64 = 3600;
+
+// Delay between proposing a new Policy and being able to apply it (2 days)
+pub const POLICY_UPDATE_DELAY: u64 = 172_800;
+
+// Storage layout version written by this code. Bump it together with a new arm in
+// migrate_vault whenever a stored struct changes. migrate_vault rewrites every vault as
+// VersionedVault::V2 whatever the starting version, so on chain:
+// 0: no SchemaVersion key; MilestoneV0 records, a Map<Address, bool> whitelist and bare
+//    vault records (VaultV1 or Vault layout).
+// 1: current Milestone and TokenList whitelist. Vaults are bare if v1 code created the
+//    contract, or already VersionedVault::V2 if it was migrated from 0.
+// 2: every vault stored as VersionedVault::V2.
+pub const SCHEMA_VERSION: u32 = 2;
+
+// Max vault records rewritten per migrate_storage call
+pub const MIGRATION_CHUNK_SIZE: u64 = 50;
+
+// Fixed-point scale of the yield-per-share index
+pub const YIELD_INDEX_SCALE: i128 = 1_000_000_000_000;
+
 // DataKey for whitelisted tokens
 #[contracttype]
 pub enum WhitelistDataKey {
-    WhitelistedTokens,
+    WhitelistedTokens,      // legacy Map<Address, bool>; moved to TokenList by migrate_storage
+    TokenMetadata(Address), // metadata cached when the token was whitelisted
+    TokenList,              // Vec<Address> of whitelisted tokens, for listing
 }
 
 // DataKey for contract storage
@@ -28,39 +51,80 @@ pub enum DataKey {
     UserVaults(Address),
     IsPaused,
     KeeperFees,
-    UserVaults(Address),
-    VaultMilestones(u64),
-    UserVaults(Address),
-    KeeperFees,
-    IsPaused,
     IsDeprecated,
     MigrationTarget,
     Token,        // yield-bearing token
     TotalShares,  // remaining initial_deposit_shares
-    TotalStaked,
+    TotalStaked,  // shares whose tokens are outside the contract (staked or unbonding)
+    AutoClaimPrefs(u64), // beneficiary-set auto-claim preferences
+    LastAutoClaim(u64),  // timestamp of the last auto_claim on a vault
+    MilestoneChallengeWindow, // seconds after unlock during which a milestone can be re-locked
+    MilestoneArbiter,         // optional address allowed to re-lock alongside the admin
+    VaultValidator(u64),      // validator the vault's staked tokens are delegated to
+    StakingRewards(u64),      // harvested staking rewards owed to a vault's owner
+    CompoundRewards(u64),     // true if harvested rewards vest with the vault instead
+    TotalStakingRewards,      // outstanding harvested rewards across all vaults (not shares)
+    UnbondingPeriod,          // seconds between unstake and withdrawable tokens
+    UnbondingQueue(u64),      // pending unbonding requests of a vault
+    LockedBalance(Address),   // unvested balance last counted towards an address's votes
+    VoteCheckpoints(Address), // voting power history of an address
+    VoteDelegate(Address),    // address receiving a beneficiary's votes (self if unset)
+    YieldIndex,               // cumulative yield per share, scaled by YIELD_INDEX_SCALE
+    OutstandingYield,         // yield recognized by sync_yield but not yet paid out
+    VaultYield(u64),          // per-vault yield accrual state
+    Policy,                   // admin-configurable vault limits
+    PendingPolicy,            // proposed Policy waiting out POLICY_UPDATE_DELAY
+    UpgradeAuthority,         // address the admin allowed to upgrade the code, e.g. the factory
+    SchemaVersion,            // storage layout version; absent for pre-versioning deployments
+    MigrationCursor,          // next vault id to rewrite during migrate_storage
+    VaultFunder(u64),         // sponsor of a create_vault_from_funder vault, refunded on return
 }
 
 mod factory;
-pub use factory::{VestingFactory, VestingFactoryClient};
+pub use factory::{DeploymentRecord, VestingFactory, VestingFactoryClient};
+
+mod staking;
+pub use staking::{StakingAdapter, StakingAdapterClient};
 
 #[contract]
 pub struct VestingContract;
 
+// Vault layout of deployments that predate delegate, staked_amount, title and is_frozen.
+// Only ever stored bare; see load_vault. No code writes VersionedVault::V1; the variant
+// just keeps the variant names in step with the Vault layouts.
+#[contracttype]
+#[derive(Clone)]
+pub struct VaultV1 {
+    pub owner: Address,
+    pub total_amount: i128,
+    pub released_amount: i128,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub keeper_fee: i128,
+    pub is_initialized: bool,
+    pub is_irrevocable: bool,
+    pub creation_time: u64,
+    pub is_transferable: bool,
+    pub step_duration: u64,
+}
+
+// Stored form of a vault record. Append a variant for each Vault layout change, keep
+// the old struct under its version name, and upgrade it in load_vault.
+#[contracttype]
+#[derive(Clone)]
+pub enum VersionedVault {
+    V1(VaultV1),
+    V2(Vault),
+}
+
 /// Vault structure with lazy initialization
-/// 
+///
 /// Supports both linear and periodic vesting schedules:
 /// - Linear vesting (step_duration = 0): Tokens vest continuously over time
 /// - Periodic vesting (step_duration > 0): Tokens vest in discrete steps (e.g., monthly)
-/// 
+///
 /// For periodic vesting, the calculation rounds down to the nearest completed step,
 /// ensuring users only receive tokens that have fully vested according to the step schedule.
-// Vault structure with lazy initialization
-    Token,       // yield-bearing token
-    TotalShares, // remaining initial_deposit_shares
-    TotalStaked,
-}
-
-// Vault structure with lazy initialization
 #[contracttype]
 #[derive(Clone)]
 pub struct Vault {
@@ -71,33 +135,20 @@ pub struct Vault {
 
     pub owner: Address,
     pub delegate: Option<Address>, // Optional delegate address for claiming
-    pub total_amount: i128,
-    pub released_amount: i128,
+    pub title: String,             // Short human-readable title (max 32 chars)
+
     pub start_time: u64,
     pub end_time: u64,
     pub creation_time: u64, // Timestamp of creation for clawback grace period
     /// Duration of each vesting step in seconds (0 = linear vesting)
-    /// 
+    ///
     /// Common values:
     /// - 0: Linear vesting (continuous)
     /// - 2,592,000: Monthly (30 days)
-    /// - 7,776,000: Quarterly (90 days) 
+    /// - 7,776,000: Quarterly (90 days)
     /// - 31,536,000: Yearly (365 days)
     pub step_duration: u64,
 
-    pub is_initialized: bool,  // Lazy initialization flag
-    pub is_irrevocable: bool,  // Security flag to prevent admin withdrawal
-    pub is_transferable: bool, // Can the beneficiary transfer this vault?
-    pub keeper_fee: i128, // Fee paid to anyone who triggers auto_claim
-        pub title: String, // Short human-readable title (max 32 chars)
-    pub is_initialized: bool, // Lazy initialization flag
-    pub is_irrevocable: bool, // Security flag to prevent admin withdrawal
-    pub creation_time: u64, // Timestamp of creation for clawback grace period
-    pub is_transferable: bool, // Can the beneficiary transfer this vault?
-    pub is_frozen: bool, // Individual vault freeze flag for security investigations
-    pub creation_time: u64, // Timestamp of creation for clawback grace period
-    pub step_duration: u64, // Duration of each vesting step in seconds (0 = linear)
-
     pub is_initialized: bool,  // Lazy initialization flag
     pub is_irrevocable: bool,  // Security flag to prevent admin withdrawal
     pub is_transferable: bool, // Can the beneficiary transfer this vault?
@@ -110,6 +161,25 @@ pub struct Milestone {
     pub id: u64,
     pub percentage: u32,
     pub is_unlocked: bool,
+    pub approver: Option<Address>, // Address (or contract) allowed to unlock; admin if None
+    pub evidence_hash: Option<BytesN<32>>, // Deliverable evidence supplied at unlock time
+    pub unlocked_by: Option<Address>,
+    pub unlocked_at: u64,
+    pub deadline: u64, // Unlock deadline timestamp (0 = no deadline)
+    pub is_forfeited: bool, // Set by expire_milestone once the deadline has passed
+    pub vesting_duration: u64, // Post-unlock vesting period in seconds (0 = released at unlock)
+    pub step_duration: u64, // Step size of the post-unlock schedule (0 = linear)
+    pub challenge_ends_at: u64, // Unlock is final (and starts vesting) at this timestamp
+}
+
+// Milestone layout of schema version 0, before approvers, evidence, deadlines, post-unlock
+// vesting and challenge windows. Rewritten as Milestone by migrate_storage(0).
+#[contracttype]
+#[derive(Clone)]
+pub struct MilestoneV0 {
+    pub id: u64,
+    pub percentage: u32,
+    pub is_unlocked: bool,
 }
 
 #[contracttype]
@@ -141,9 +211,133 @@ pub struct VaultCreated {
     pub title: String,
 }
 
-// mod factory;
-// pub use factory::{VestingFactory, VestingFactoryClient};
+// Beneficiary-controlled limits on when keepers may auto-claim a vault
+#[contracttype]
+#[derive(Clone)]
+pub struct AutoClaimPreferences {
+    pub min_claim_amount: i128, // Minimum claimable amount before a keeper may claim
+    pub min_interval: u64,      // Minimum seconds between two auto-claims
+    pub opted_out: bool,        // Disable auto-claim for this vault entirely
+}
+
+// Harvested staking rewards of a vault. Kept outside of TotalShares so the
+// vesting invariant only ever covers principal.
+#[contracttype]
+#[derive(Clone)]
+pub struct StakingRewards {
+    pub claimable: i128,           // Withdrawable immediately
+    pub compounded: i128,          // Vests alongside the vault's own schedule
+    pub compounded_released: i128, // Portion of `compounded` already withdrawn
+}
+
+// Tokens unstaked from the staking contract that become withdrawable at `maturity`
+#[contracttype]
+#[derive(Clone)]
+pub struct UnbondingRequest {
+    pub amount: i128,
+    pub maturity: u64,
+}
+
+// Yield accrual state of a vault. `shares` is the vault's unreleased balance as of
+// the last checkpoint at `index`.
+#[contracttype]
+#[derive(Clone)]
+pub struct VaultYieldState {
+    pub shares: i128,
+    pub index: i128,
+    pub accrued: i128,
+}
+
+// Voting power of an address from `timestamp` onwards
+#[contracttype]
+#[derive(Clone)]
+pub struct VotesCheckpoint {
+    pub timestamp: u64,
+    pub votes: i128,
+}
+
+// Token details cached at whitelisting time
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenMetadata {
+    pub decimals: u32,
+    pub symbol: String,
+    pub locked_cap: i128, // max tokens of this token locked in vaults at once
+}
+
+// Everything initialize needs, accepted exactly once
+#[contracttype]
+#[derive(Clone)]
+pub struct InitConfig {
+    pub admin: Address,
+    pub token: Address,          // yield-bearing vesting token, whitelisted without a cap
+    pub initial_supply: i128,    // tokens already transferred to the contract
+    pub whitelist: Vec<Address>, // additional tokens, e.g. for rescue and migration
+    pub policy: Policy,
+}
+
+// Limits applied to every vault creation path
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Policy {
+    pub min_duration: u64,
+    pub max_duration: u64,
+    pub max_keeper_fee: i128,
+    pub max_step_count: u64, // max number of steps for stepped vaults
+    pub clawback_grace_period: u64,
+    pub max_batch_size: u32,
+    pub min_vault_amount: i128,
+    pub max_vault_amount: i128,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy {
+            min_duration: 0,
+            max_duration: MAX_DURATION,
+            max_keeper_fee: i128::MAX,
+            max_step_count: u64::MAX,
+            clawback_grace_period: DEFAULT_CLAWBACK_GRACE_PERIOD,
+            max_batch_size: u32::MAX,
+            min_vault_amount: 0,
+            max_vault_amount: i128::MAX,
+        }
+    }
+}
+
+// A proposed Policy and the earliest time it can be applied
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingPolicy {
+    pub policy: Policy,
+    pub effective_at: u64,
+}
+
+// Token balance held by the contract versus what it owes in that token.
+// A negative surplus is a deficit.
+#[contracttype]
+#[derive(Clone)]
+pub struct TokenReconciliation {
+    pub token: Address,
+    pub balance: i128,
+    pub vault_liabilities: i128, // unreleased vault shares, including staked ones
+    pub staked: i128,            // shares held by the staking contract (staked or unbonding)
+    pub admin_balance: i128,
+    pub staking_rewards: i128,
+    pub outstanding_yield: i128,
+    pub keeper_fees: i128, // paid to keepers so far; already transferred, not a liability
+    pub surplus: i128,
+}
 
+// Per-vault outcome of a batch auto-claim
+#[contracttype]
+#[derive(Clone)]
+pub struct AutoClaimResult {
+    pub vault_id: u64,
+    pub claimed: bool, // false if the vault was skipped
+    pub beneficiary_amount: i128,
+    pub keeper_fee: i128,
+}
 
 #[contractimpl]
 #[allow(deprecated)]
@@ -159,151 +353,465 @@ impl VestingContract {
         }
     }
 
-    fn require_valid_duration(start_time: u64, end_time: u64) {
+    fn require_valid_duration(env: &Env, start_time: u64, end_time: u64) {
         let duration = end_time
             .checked_sub(start_time)
             .unwrap_or_else(|| panic!("end_time must be >= start_time"));
-        if duration > MAX_DURATION {
-            panic!("duration exceeds MAX_DURATION");
+        if duration > Self::policy(env).max_duration {
+            panic!("duration exceeds max_duration");
+        }
+    }
+
+    // Check a new vault's parameters against the current Policy
+    fn require_valid_vault(
+        env: &Env,
+        amount: i128,
+        start_time: u64,
+        end_time: u64,
+        keeper_fee: i128,
+        step_duration: u64,
+    ) {
+        Self::require_valid_duration(env, start_time, end_time);
+
+        let policy = Self::policy(env);
+        let duration = end_time - start_time;
+        if duration < policy.min_duration {
+            panic!("duration below min_duration");
+        }
+        if amount < policy.min_vault_amount {
+            panic!("amount below min_vault_amount");
+        }
+        if amount > policy.max_vault_amount {
+            panic!("amount exceeds max_vault_amount");
+        }
+        if keeper_fee > policy.max_keeper_fee {
+            panic!("keeper_fee exceeds max_keeper_fee");
+        }
+        if step_duration > 0 && duration.div_ceil(step_duration) > policy.max_step_count {
+            panic!("step count exceeds max_step_count");
+        }
+    }
+
+    fn require_valid_batch_size(env: &Env, size: u32) {
+        if size > Self::policy(env).max_batch_size {
+            panic!("batch exceeds max_batch_size");
+        }
+    }
+
+    fn validate_policy(policy: &Policy) {
+        if policy.max_duration == 0 || policy.min_duration > policy.max_duration {
+            panic!("Invalid policy durations");
+        }
+        if policy.min_vault_amount < 0 || policy.min_vault_amount > policy.max_vault_amount {
+            panic!("Invalid policy vault amounts");
+        }
+        if policy.max_keeper_fee < 0 {
+            panic!("Invalid policy max_keeper_fee");
+        }
+        if policy.max_step_count == 0 || policy.max_batch_size == 0 {
+            panic!("Policy limits must be positive");
         }
     }
 
-    // Admin-only: Add token to whitelist
-    pub fn add_to_whitelist(env: Env, token: Address) {
+    fn policy(env: &Env) -> Policy {
+        env.storage()
+            .instance()
+            .get(&DataKey::Policy)
+            .unwrap_or_default()
+    }
+
+    pub fn get_policy(env: Env) -> Policy {
+        Self::policy(&env)
+    }
+
+    pub fn get_pending_policy(env: Env) -> Option<PendingPolicy> {
+        env.storage().instance().get(&DataKey::PendingPolicy)
+    }
+
+    // Admin-only: queue a new Policy; it can be applied after POLICY_UPDATE_DELAY
+    pub fn propose_policy(env: Env, policy: Policy) {
+        Self::require_admin(&env);
+        Self::validate_policy(&policy);
+
+        let effective_at = env.ledger().timestamp() + POLICY_UPDATE_DELAY;
+        env.storage().instance().set(
+            &DataKey::PendingPolicy,
+            &PendingPolicy {
+                policy: policy.clone(),
+                effective_at,
+            },
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "PolicyProposed"),),
+            (policy, effective_at),
+        );
+    }
+
+    // Admin-only: drop a queued Policy before it is applied
+    pub fn cancel_policy(env: Env) {
         Self::require_admin(&env);
-        let mut whitelist: Map<Address, bool> = env
+        if !env.storage().instance().has(&DataKey::PendingPolicy) {
+            panic!("No pending policy");
+        }
+        env.storage().instance().remove(&DataKey::PendingPolicy);
+    }
+
+    // Apply the queued Policy once its delay has passed. Anyone can call.
+    pub fn apply_policy(env: Env) {
+        let pending: PendingPolicy = env
             .storage()
             .instance()
-            .get(&WhitelistDataKey::WhitelistedTokens)
-            .unwrap_or(Map::new(&env));
-        whitelist.set(token.clone(), true);
+            .get(&DataKey::PendingPolicy)
+            .unwrap_or_else(|| panic!("No pending policy"));
+        if env.ledger().timestamp() < pending.effective_at {
+            panic!("Policy update delay not elapsed");
+        }
+
+        env.storage().instance().set(&DataKey::Policy, &pending.policy);
+        env.storage().instance().remove(&DataKey::PendingPolicy);
+
+        env.events().publish(
+            (Symbol::new(&env, "PolicyUpdated"),),
+            pending.policy,
+        );
+    }
+
+    // Admin-only: Add token to whitelist, caching its decimals and symbol.
+    // `locked_cap` limits how many of its tokens can be locked in vaults at once.
+    pub fn add_to_whitelist(env: Env, token: Address, locked_cap: i128) {
+        Self::require_admin(&env);
+        Self::internal_add_to_whitelist(&env, &token, locked_cap);
+    }
+
+    // Admin-only: Remove token from whitelist. Removing the vesting token blocks new vaults.
+    pub fn remove_from_whitelist(env: Env, token: Address) {
+        Self::require_admin(&env);
+        if !Self::is_token_whitelisted(&env, &token) {
+            panic!("Token is not whitelisted");
+        }
+
+        let mut whitelist = Self::get_whitelisted_tokens(env.clone());
+        if let Some(index) = whitelist.first_index_of(&token) {
+            whitelist.remove(index);
+            env.storage()
+                .instance()
+                .set(&WhitelistDataKey::TokenList, &whitelist);
+        }
+        env.storage()
+            .instance()
+            .remove(&WhitelistDataKey::TokenMetadata(token.clone()));
+
+        env.events()
+            .publish((Symbol::new(&env, "TokenRemovedFromWhitelist"), token), ());
+    }
+
+    pub fn get_whitelisted_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&WhitelistDataKey::TokenList)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_token_metadata(env: Env, token: Address) -> Option<TokenMetadata> {
+        env.storage()
+            .instance()
+            .get(&WhitelistDataKey::TokenMetadata(token))
+    }
+
+    // Internal helper: whitelist a token or refresh its cached metadata.
+    // Does NOT check auth — caller is responsible for that.
+    fn internal_add_to_whitelist(env: &Env, token: &Address, locked_cap: i128) {
+        if locked_cap < 0 {
+            panic!("locked_cap must be non-negative");
+        }
+
+        let token_client = token::Client::new(env, token);
+        let metadata = TokenMetadata {
+            decimals: token_client.decimals(),
+            symbol: token_client.symbol(),
+            locked_cap,
+        };
+
+        let mut whitelist = Self::get_whitelisted_tokens(env.clone());
+        if !whitelist.contains(token) {
+            whitelist.push_back(token.clone());
+            env.storage()
+                .instance()
+                .set(&WhitelistDataKey::TokenList, &whitelist);
+        }
         env.storage()
             .instance()
-            .set(&WhitelistDataKey::WhitelistedTokens, &whitelist);
+            .set(&WhitelistDataKey::TokenMetadata(token.clone()), &metadata);
+
+        env.events().publish(
+            (Symbol::new(env, "TokenWhitelisted"), token.clone()),
+            metadata,
+        );
     }
 
     // Check if token is whitelisted
     fn is_token_whitelisted(env: &Env, token: &Address) -> bool {
-        let whitelist: Map<Address, bool> = env
+        env.storage()
+            .instance()
+            .has(&WhitelistDataKey::TokenMetadata(token.clone()))
+    }
+
+    // Vaults are denominated in the vesting token, so every creation path requires it to
+    // be whitelisted and `amount` more locked tokens to fit under its cap
+    fn require_token_capacity(env: &Env, amount: i128) {
+        let token: Address = env
             .storage()
             .instance()
-            .get(&WhitelistDataKey::WhitelistedTokens)
-            .unwrap_or(Map::new(env));
-        whitelist.get(token.clone()).unwrap_or(false)
+            .get(&DataKey::Token)
+            .unwrap_or_else(|| panic!("Token not set"));
+        let metadata: TokenMetadata = env
+            .storage()
+            .instance()
+            .get(&WhitelistDataKey::TokenMetadata(token))
+            .unwrap_or_else(|| panic!("Token is not whitelisted"));
+
+        let total_shares: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalShares)
+            .unwrap_or(0);
+        if total_shares + amount > metadata.locked_cap {
+            panic!("Token locked_cap exceeded");
+        }
     }
 
-    // Initialize contract with initial supply
-    pub fn initialize(env: Env, admin: Address, initial_supply: i128) {
+    // One-shot initialization by the admin. `initial_supply` must already be held by the
+    // contract; use fund to add tokens afterwards.
+    pub fn initialize(env: Env, config: InitConfig) {
         Self::require_not_deprecated(&env);
-        env.storage().instance().set(&DataKey::InitialSupply, &initial_supply);
-        env.storage().instance().set(&DataKey::AdminBalance, &initial_supply);
+        if env.storage().instance().has(&DataKey::AdminAddress) {
+            panic!("Already initialized");
+        }
+        config.admin.require_auth();
+        if config.initial_supply < 0 {
+            panic!("Initial supply must be non-negative");
+        }
+        let balance =
+            token::Client::new(&env, &config.token).balance(&env.current_contract_address());
+        if balance < config.initial_supply {
+            panic!("Initial supply not held by contract");
+        }
+        Self::validate_policy(&config.policy);
 
-        env.storage().instance().set(&DataKey::InitialSupply, &initial_supply);
-        env.storage().instance().set(&DataKey::AdminBalance, &initial_supply);
-        env.storage().instance().set(&DataKey::AdminAddress, &admin);
         env.storage()
             .instance()
-            .set(&DataKey::InitialSupply, &initial_supply);
+            .set(&DataKey::AdminAddress, &config.admin);
+        env.storage().instance().set(&DataKey::Token, &config.token);
         env.storage()
             .instance()
-            .set(&DataKey::AdminBalance, &initial_supply);
-
-        env.storage().instance().set(&DataKey::AdminAddress, &admin);
+            .set(&DataKey::InitialSupply, &config.initial_supply);
+        env.storage()
+            .instance()
+            .set(&DataKey::AdminBalance, &config.initial_supply);
+        env.storage().instance().set(&DataKey::Policy, &config.policy);
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
 
         env.storage().instance().set(&DataKey::VaultCount, &0u64);
 
         // Initialize pause state to false (unpaused)
         env.storage().instance().set(&DataKey::IsPaused, &false);
 
-        // Initialize whitelisted tokens map
-        let whitelist: Map<Address, bool> = Map::new(&env);
-        env.storage()
         // Initialize deprecated state to false (active)
         env.storage().instance().set(&DataKey::IsDeprecated, &false);
 
         // Clear migration target on init
         env.storage().instance().remove(&DataKey::MigrationTarget);
 
-        // Initialize whitelisted tokens map
-        let whitelist: Map<Address, bool> = Map::new(&env);
+        // Initialize the whitelist with the vesting token and any extra tokens
         env.storage()
             .instance()
-            .set(&WhitelistDataKey::WhitelistedTokens, &whitelist);
+            .set(&WhitelistDataKey::TokenList, &Vec::<Address>::new(&env));
+        Self::internal_add_to_whitelist(&env, &config.token, i128::MAX);
+        for token_address in config.whitelist.iter() {
+            Self::internal_add_to_whitelist(&env, &token_address, i128::MAX);
+        }
 
         env.storage().instance().set(&DataKey::TotalShares, &0i128);
         env.storage().instance().set(&DataKey::TotalStaked, &0i128);
-    }
 
-    pub fn set_token(env: Env, token: Address) {
-        Self::require_admin(&env);
-        if env.storage().instance().has(&DataKey::Token) {
-            panic!("Token already set");
-        }
-        env.storage().instance().set(&DataKey::Token, &token);
+        env.events().publish(
+            (Symbol::new(&env, "Initialized"),),
+            (config.admin, config.token, config.initial_supply),
+        );
     }
 
     fn get_token_client(env: &Env) -> token::Client<'_> {
         let token: Address = env
             .storage()
             .instance()
-            .set(&WhitelistDataKey::WhitelistedTokens, &whitelist);
+            .get(&DataKey::Token)
+            .unwrap_or_else(|| panic!("Token not set"));
+        token::Client::new(env, &token)
     }
 
-    // Helper function to check if caller is admin
-    fn require_admin(env: &Env) {
-        let admin: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::AdminAddress)
-            .unwrap_or_else(|| panic!("Admin not set"));
-        admin.require_auth();
+    // Pull `amount` tokens from `from` into the contract and credit them to AdminBalance
+    pub fn fund(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        Self::internal_fund(&env, &from, amount);
     }
 
-    fn require_milestones_configured(env: &Env, vault_id: u64) -> Vec<Milestone> {
-        let milestones: Vec<Milestone> = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultMilestones(vault_id))
-            .unwrap_or(Vec::new(env));
-        if milestones.is_empty() {
-            panic!("Milestones not configured");
+    // Return unallocated tokens from AdminBalance to the treasury (admin only)
+    pub fn withdraw_admin_balance(env: Env, to: Address, amount: i128) {
+        Self::require_admin(&env);
+        if amount <= 0 {
+            panic!("Amount must be positive");
         }
-        milestones
-    }
 
-    fn unlocked_percentage(milestones: &Vec<Milestone>) -> u32 {
-        let mut pct: u32 = 0;
-        for m in milestones.iter() {
-            if m.is_unlocked {
-                pct = pct.saturating_add(m.percentage);
-            }
-        }
-        if pct > 100 {
-            100
-        } else {
-            pct
+        let admin_balance: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminBalance)
+            .unwrap_or(0);
+        if admin_balance < amount {
+            panic!("Insufficient admin balance");
         }
-    }
-
-    fn unlocked_amount(total_amount: i128, unlocked_percentage: u32) -> i128 {
-        (total_amount * unlocked_percentage as i128) / 100i128
-    }
-
-    // Propose a new admin (first step of two-step process)
-    pub fn propose_new_admin(env: Env, new_admin: Address) {
-        Self::require_admin(&env);
         env.storage()
             .instance()
-            .set(&DataKey::ProposedAdmin, &new_admin);
-    }
+            .set(&DataKey::AdminBalance, &(admin_balance - amount));
 
-    // Accept admin ownership (second step of two-step process)
-    pub fn accept_ownership(env: Env) {
-        let proposed_admin: Address = env
+        // Withdrawn tokens were never distributed, so they leave the supply as well
+        let initial_supply: i128 = env
             .storage()
             .instance()
-            .get(&DataKey::ProposedAdmin)
-            .unwrap_or_else(|| panic!("No proposed admin found"));
+            .get(&DataKey::InitialSupply)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::InitialSupply, &(initial_supply - amount));
+
+        Self::get_token_client(&env).transfer(&env.current_contract_address(), &to, &amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "AdminBalanceWithdrawn"), to),
+            amount,
+        );
+    }
+
+    // Internal helper: transfer tokens in and credit AdminBalance and InitialSupply.
+    // Does NOT check auth — caller is responsible for `from.require_auth()`.
+    fn internal_fund(env: &Env, from: &Address, amount: i128) {
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        Self::get_token_client(env).transfer(from, env.current_contract_address(), &amount);
+
+        let admin_balance: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminBalance)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::AdminBalance, &(admin_balance + amount));
+
+        let initial_supply: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::InitialSupply)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::InitialSupply, &(initial_supply + amount));
+
+        env.events().publish(
+            (Symbol::new(env, "Funded"), from.clone()),
+            amount,
+        );
+    }
+
+    // Helper function to check if caller is admin
+    fn require_admin(env: &Env) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminAddress)
+            .unwrap_or_else(|| panic!("Admin not set"));
+        admin.require_auth();
+    }
+
+    fn require_milestones_configured(env: &Env, vault_id: u64) -> Vec<Milestone> {
+        let milestones: Vec<Milestone> = env
+            .storage()
+            .instance()
+            .get(&DataKey::VaultMilestones(vault_id))
+            .unwrap_or(Vec::new(env));
+        if milestones.is_empty() {
+            panic!("Milestones not configured");
+        }
+        milestones
+    }
+
+    // Amount released by a vault's milestones at the current time. Each unlocked milestone
+    // releases its tranche over its own sub-schedule once its challenge window ends. Forfeited
+    // milestones are counted as fully released: their share was already added to
+    // released_amount when it was returned to the admin.
+    fn milestone_vested_amount(env: &Env, total_amount: i128, milestones: &Vec<Milestone>) -> i128 {
+        let now = env.ledger().timestamp();
+        let mut full_pct: u32 = 0;
+        let mut partial: i128 = 0;
+        for m in milestones.iter() {
+            if m.is_forfeited {
+                full_pct = full_pct.saturating_add(m.percentage);
+            } else if m.is_unlocked && now >= m.challenge_ends_at {
+                let elapsed = now - m.challenge_ends_at;
+                if elapsed >= m.vesting_duration {
+                    full_pct = full_pct.saturating_add(m.percentage);
+                } else {
+                    let tranche = Self::unlocked_amount(total_amount, m.percentage);
+                    let effective_elapsed = match elapsed.checked_div(m.step_duration) {
+                        Some(steps) => steps * m.step_duration,
+                        None => elapsed,
+                    };
+                    partial += (tranche * effective_elapsed as i128) / m.vesting_duration as i128;
+                }
+            }
+        }
+        (Self::unlocked_amount(total_amount, full_pct.min(100)) + partial).min(total_amount)
+    }
+
+    // Amount of a vault's principal vested so far, by milestones if configured,
+    // otherwise by its time schedule
+    fn vested_amount(env: &Env, vault_id: u64, vault: &Vault) -> i128 {
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::VaultMilestones(vault_id))
+        {
+            let milestones = Self::require_milestones_configured(env, vault_id);
+            Self::milestone_vested_amount(env, vault.total_amount, &milestones)
+        } else {
+            Self::calculate_time_vested_amount(env, vault)
+        }
+    }
+
+    fn unlocked_amount(total_amount: i128, unlocked_percentage: u32) -> i128 {
+        (total_amount * unlocked_percentage as i128) / 100i128
+    }
+
+    // Propose a new admin (first step of two-step process)
+    pub fn propose_new_admin(env: Env, new_admin: Address) {
+        Self::require_admin(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::ProposedAdmin, &new_admin);
+    }
+
+    // Accept admin ownership (second step of two-step process)
+    pub fn accept_ownership(env: Env) {
+        let proposed_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::ProposedAdmin)
+            .unwrap_or_else(|| panic!("No proposed admin found"));
         proposed_admin.require_auth();
         env.storage()
             .instance()
@@ -320,24 +828,15 @@ impl VestingContract {
             panic!("v2_contract_address must differ from current contract");
         }
 
-        proposed_admin.require_auth();
-
+        env.storage().instance().set(&DataKey::IsDeprecated, &true);
         env.storage()
             .instance()
-            .set(&DataKey::AdminAddress, &proposed_admin);
             .set(&DataKey::MigrationTarget, &v2_contract_address);
 
-        let whitelist: Map<Address, bool> = env
-            .storage()
-            .instance()
-            .get(&WhitelistDataKey::WhitelistedTokens)
-            .unwrap_or(Map::new(&env));
+        let whitelist = Self::get_whitelisted_tokens(env.clone());
 
         let mut migrated: Map<Address, i128> = Map::new(&env);
-        for (token_address, allowed) in whitelist.iter() {
-            if !allowed {
-                continue;
-            }
+        for token_address in whitelist.iter() {
             let token_client = token::Client::new(&env, &token_address);
             let balance: i128 = token_client.balance(&env.current_contract_address());
             if balance > 0 {
@@ -358,12 +857,8 @@ impl VestingContract {
         let timestamp = env.ledger().timestamp();
         env.events()
             .publish((Symbol::new(&env, "ContractDeprecated"),), (v2_contract_address, timestamp));
-        env.events().publish(
-            (Symbol::new(&env, "ContractDeprecated"),),
-            v2_contract_address,
-        );
 
-        env.storage().instance().remove(&DataKey::ProposedAdmin);
+        migrated
     }
 
     // Get current admin address
@@ -379,6 +874,235 @@ impl VestingContract {
         env.storage().instance().get(&DataKey::ProposedAdmin)
     }
 
+    // Admin-only: opt in to (or out of) code upgrades by `authority`, e.g. the factory
+    pub fn set_upgrade_authority(env: Env, authority: Option<Address>) {
+        Self::require_admin(&env);
+        match authority.clone() {
+            Some(authority) => env
+                .storage()
+                .instance()
+                .set(&DataKey::UpgradeAuthority, &authority),
+            None => env.storage().instance().remove(&DataKey::UpgradeAuthority),
+        }
+        env.events()
+            .publish((Symbol::new(&env, "UpgradeAuthoritySet"),), authority);
+    }
+
+    pub fn get_upgrade_authority(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::UpgradeAuthority)
+    }
+
+    // Replace this contract's code. Callable by the admin, or by the upgrade authority the
+    // admin opted in to. Run migrate_storage afterwards if the new code bumps SCHEMA_VERSION.
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        let admin = Self::get_admin(env.clone());
+        let authority: Option<Address> = env.storage().instance().get(&DataKey::UpgradeAuthority);
+        if caller != admin && authority != Some(caller.clone()) {
+            panic!("Caller is not admin or upgrade authority");
+        }
+        caller.require_auth();
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events()
+            .publish((Symbol::new(&env, "Upgraded"),), (caller, new_wasm_hash));
+    }
+
+    pub fn get_schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(0)
+    }
+
+    // Admin-only: rewrite vault records stored under `from_version` into the next schema
+    // version, at most MIGRATION_CHUNK_SIZE vaults per call. The last call of a pass also
+    // migrates contract-wide records. Call repeatedly until it returns true, then again with
+    // the next version until get_schema_version() == SCHEMA_VERSION.
+    pub fn migrate_storage(env: Env, from_version: u32) -> bool {
+        Self::require_admin(&env);
+
+        let stored_version = Self::get_schema_version(env.clone());
+        if from_version != stored_version {
+            panic!("Storage is not at from_version");
+        }
+        if stored_version >= SCHEMA_VERSION {
+            panic!("Storage already at current schema");
+        }
+
+        let vault_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::VaultCount)
+            .unwrap_or(0);
+        let start: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MigrationCursor)
+            .unwrap_or(1);
+        let end = (start + MIGRATION_CHUNK_SIZE - 1).min(vault_count);
+
+        for vault_id in start..=end {
+            Self::migrate_vault(&env, from_version, vault_id);
+        }
+
+        if end < vault_count {
+            env.storage()
+                .instance()
+                .set(&DataKey::MigrationCursor, &(end + 1));
+            return false;
+        }
+
+        if from_version == 0 {
+            Self::migrate_legacy_whitelist(&env);
+        }
+        env.storage().instance().remove(&DataKey::MigrationCursor);
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &(from_version + 1));
+        env.events().publish(
+            (Symbol::new(&env, "StorageMigrated"),),
+            (from_version, from_version + 1),
+        );
+        true
+    }
+
+    // Per-version rewrite of one vault's records; see SCHEMA_VERSION for the layouts.
+    // load_vault reads bare vaults of either layout, and save_vault re-stores them versioned.
+    fn migrate_vault(env: &Env, from_version: u32, vault_id: u64) {
+        match from_version {
+            0 => {
+                if let Some(vault) = Self::load_vault(env, vault_id) {
+                    Self::save_vault(env, vault_id, &vault);
+                }
+                Self::migrate_milestones_v0(env, vault_id);
+            }
+            1 => {
+                if let Some(vault) = Self::load_vault(env, vault_id) {
+                    Self::save_vault(env, vault_id, &vault);
+                }
+            }
+            _ => panic!("No migration from this schema version"),
+        }
+    }
+
+    // Rewrite a vault's MilestoneV0 records as Milestone with no approver, deadline,
+    // post-unlock vesting or challenge window, so they release exactly as before.
+    // Lists already in the current layout (set after the code upgrade) are left alone.
+    fn migrate_milestones_v0(env: &Env, vault_id: u64) {
+        let key = DataKey::VaultMilestones(vault_id);
+        let raw: Vec<Val> = match env.storage().instance().get(&key) {
+            Some(raw) => raw,
+            None => return,
+        };
+
+        let mut milestones: Vec<Milestone> = Vec::new(env);
+        for item in raw.iter() {
+            let fields = Map::<Symbol, Val>::try_from_val(env, &item)
+                .unwrap_or_else(|_| panic!("Unreadable milestone record"));
+            if fields.contains_key(Symbol::new(env, "approver")) {
+                return;
+            }
+            let old = MilestoneV0::try_from_val(env, &item)
+                .unwrap_or_else(|_| panic!("Unreadable milestone record"));
+            milestones.push_back(Milestone {
+                id: old.id,
+                percentage: old.percentage,
+                is_unlocked: old.is_unlocked,
+                approver: None,
+                evidence_hash: None,
+                unlocked_by: None,
+                unlocked_at: 0,
+                deadline: 0,
+                is_forfeited: false,
+                vesting_duration: 0,
+                step_duration: 0,
+                challenge_ends_at: 0,
+            });
+        }
+        env.storage().instance().set(&key, &milestones);
+    }
+
+    // Move the legacy whitelist map into TokenList, caching metadata for each allowed token.
+    // Pre-series deployments did not whitelist the vesting token, so it is added uncapped.
+    fn migrate_legacy_whitelist(env: &Env) {
+        let legacy: Map<Address, bool> = env
+            .storage()
+            .instance()
+            .get(&WhitelistDataKey::WhitelistedTokens)
+            .unwrap_or(Map::new(env));
+        for (token_address, allowed) in legacy.iter() {
+            if allowed {
+                Self::internal_add_to_whitelist(env, &token_address, i128::MAX);
+            }
+        }
+        env.storage()
+            .instance()
+            .remove(&WhitelistDataKey::WhitelistedTokens);
+
+        if let Some(token) = env.storage().instance().get::<_, Address>(&DataKey::Token) {
+            if !Self::is_token_whitelisted(env, &token) {
+                Self::internal_add_to_whitelist(env, &token, i128::MAX);
+            }
+        }
+    }
+
+    // Read a vault record of any stored version, upgraded to the current Vault layout.
+    // Nothing is written back here; the next save_vault stores the current version.
+    fn load_vault(env: &Env, vault_id: u64) -> Option<Vault> {
+        let raw: Val = env
+            .storage()
+            .instance()
+            .get(&DataKey::VaultData(vault_id))?;
+        match VersionedVault::try_from_val(env, &raw) {
+            Ok(VersionedVault::V2(vault)) => Some(vault),
+            Ok(VersionedVault::V1(vault)) => Some(Self::upgrade_vault_v1(env, vault)),
+            // Records written before versioning are bare structs of either layout. Decoding
+            // a struct from a map with other fields traps, so pick the layout by its fields.
+            Err(_) => {
+                let fields = Map::<Symbol, Val>::try_from_val(env, &raw)
+                    .unwrap_or_else(|_| panic!("Unreadable vault record"));
+                if fields.contains_key(Symbol::new(env, "is_frozen")) {
+                    Some(
+                        Vault::try_from_val(env, &raw)
+                            .unwrap_or_else(|_| panic!("Unreadable vault record")),
+                    )
+                } else {
+                    let vault = VaultV1::try_from_val(env, &raw)
+                        .unwrap_or_else(|_| panic!("Unreadable vault record"));
+                    Some(Self::upgrade_vault_v1(env, vault))
+                }
+            }
+        }
+    }
+
+    fn save_vault(env: &Env, vault_id: u64, vault: &Vault) {
+        env.storage().instance().set(
+            &DataKey::VaultData(vault_id),
+            &VersionedVault::V2(vault.clone()),
+        );
+    }
+
+    fn upgrade_vault_v1(env: &Env, vault: VaultV1) -> Vault {
+        Vault {
+            total_amount: vault.total_amount,
+            released_amount: vault.released_amount,
+            keeper_fee: vault.keeper_fee,
+            staked_amount: 0,
+            owner: vault.owner,
+            delegate: None,
+            title: String::from_str(env, ""),
+            start_time: vault.start_time,
+            end_time: vault.end_time,
+            creation_time: vault.creation_time,
+            step_duration: vault.step_duration,
+            is_initialized: vault.is_initialized,
+            is_irrevocable: vault.is_irrevocable,
+            is_transferable: vault.is_transferable,
+            is_frozen: false,
+        }
+    }
+
     // Toggle pause state (Admin only) - "Big Red Button" for emergency pause
     pub fn toggle_pause(env: Env) {
         Self::require_admin(&env);
@@ -413,10 +1137,7 @@ impl VestingContract {
     pub fn freeze_vault(env: Env, vault_id: u64) {
         Self::require_admin(&env);
 
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         if vault.is_frozen {
@@ -424,9 +1145,7 @@ impl VestingContract {
         }
 
         vault.is_frozen = true;
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
+        Self::save_vault(&env, vault_id, &vault);
 
         env.events().publish(
             (Symbol::new(&env, "VaultFrozen"), vault_id),
@@ -438,10 +1157,7 @@ impl VestingContract {
     pub fn unfreeze_vault(env: Env, vault_id: u64) {
         Self::require_admin(&env);
 
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         if !vault.is_frozen {
@@ -449,9 +1165,7 @@ impl VestingContract {
         }
 
         vault.is_frozen = false;
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
+        Self::save_vault(&env, vault_id, &vault);
 
         env.events().publish(
             (Symbol::new(&env, "VaultUnfrozen"), vault_id),
@@ -461,10 +1175,7 @@ impl VestingContract {
 
     // Check if a specific vault is frozen
     pub fn is_vault_frozen(env: Env, vault_id: u64) -> bool {
-        let vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         vault.is_frozen
@@ -482,36 +1193,139 @@ impl VestingContract {
         is_transferable: bool,
         step_duration: u64,
     ) -> u64 {
+        Self::require_admin(&env);
+        Self::internal_create_vault_full(
+            &env,
+            owner,
+            amount,
+            start_time,
+            end_time,
+            keeper_fee,
+            is_revocable,
+            is_transferable,
+            step_duration,
+        )
+    }
 
+    // Admin-only: create a vault funded by a third-party sponsor. The sponsor's tokens are
+    // pulled into the contract and allocated to this grant only; AdminBalance is unchanged.
+    // Unvested tokens returned by revoke, clawback or forfeit go back to the sponsor.
+    pub fn create_vault_from_funder(
+        env: Env,
+        funder: Address,
+        owner: Address,
+        amount: i128,
+        start_time: u64,
+        end_time: u64,
+        keeper_fee: i128,
+        is_revocable: bool,
+        is_transferable: bool,
+        step_duration: u64,
+    ) -> u64 {
         Self::require_admin(&env);
-        Self::require_valid_duration(start_time, end_time);
+        funder.require_auth();
+        Self::internal_fund(&env, &funder, amount);
+        let vault_id = Self::internal_create_vault_full(
+            &env,
+            owner,
+            amount,
+            start_time,
+            end_time,
+            keeper_fee,
+            is_revocable,
+            is_transferable,
+            step_duration,
+        );
 
-        let mut vault_count: u64 = env
-            .storage()
+        env.storage()
             .instance()
-            .get(&DataKey::VaultCount)
-            .unwrap_or(0);
-        vault_count += 1;
+            .set(&DataKey::VaultFunder(vault_id), &funder);
 
-        let mut admin_balance: i128 = env
+        env.events().publish(
+            (Symbol::new(&env, "VaultFunded"), vault_id),
+            (funder, amount),
+        );
+
+        vault_id
+    }
+
+    pub fn get_vault_funder(env: Env, vault_id: u64) -> Option<Address> {
+        env.storage().instance().get(&DataKey::VaultFunder(vault_id))
+    }
+
+    // Internal helper: send `amount` returned from a sponsored vault back to its funder.
+    // Returns the part still owed to AdminBalance: all of it for admin-funded vaults, else 0.
+    // Refunds leave the contract, so they come off InitialSupply like withdrawals do.
+    // Callers must cap `amount` at the vault's liquid balance (see internal_liquid_balance).
+    fn refund_funder(env: &Env, vault_id: u64, amount: i128) -> i128 {
+        let funder: Address = match env.storage().instance().get(&DataKey::VaultFunder(vault_id)) {
+            Some(funder) => funder,
+            None => return amount,
+        };
+        if amount <= 0 {
+            return 0;
+        }
+
+        let initial_supply: i128 = env
             .storage()
             .instance()
-            .get(&DataKey::AdminBalance)
+            .get(&DataKey::InitialSupply)
             .unwrap_or(0);
-        if admin_balance < amount {
-            panic!("Insufficient admin balance");
-        }
-        admin_balance -= amount;
         env.storage()
             .instance()
-            .set(&DataKey::AdminBalance, &admin_balance);
+            .set(&DataKey::InitialSupply, &(initial_supply - amount));
 
-        let now = env.ledger().timestamp();
-
-        let is_irrevocable = !is_revocable;
+        Self::get_token_client(env).transfer(&env.current_contract_address(), &funder, &amount);
 
-        let vault = Vault {
-            title: String::from_slice(&env, ""),
+        env.events().publish(
+            (Symbol::new(env, "FunderRefunded"), vault_id),
+            (funder, amount),
+        );
+        0
+    }
+
+    // Internal helper: allocate `amount` from AdminBalance to a fully initialized vault.
+    // Does NOT check auth — caller is responsible for that.
+    fn internal_create_vault_full(
+        env: &Env,
+        owner: Address,
+        amount: i128,
+        start_time: u64,
+        end_time: u64,
+        keeper_fee: i128,
+        is_revocable: bool,
+        is_transferable: bool,
+        step_duration: u64,
+    ) -> u64 {
+        Self::require_valid_vault(env, amount, start_time, end_time, keeper_fee, step_duration);
+        Self::require_token_capacity(env, amount);
+
+        let mut vault_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::VaultCount)
+            .unwrap_or(0);
+        vault_count += 1;
+
+        let mut admin_balance: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminBalance)
+            .unwrap_or(0);
+        if admin_balance < amount {
+            panic!("Insufficient admin balance");
+        }
+        admin_balance -= amount;
+        env.storage()
+            .instance()
+            .set(&DataKey::AdminBalance, &admin_balance);
+
+        let now = env.ledger().timestamp();
+
+        let is_irrevocable = !is_revocable;
+
+        let vault = Vault {
+            title: String::from_str(env, ""),
             owner: owner.clone(),
             delegate: None,
             total_amount: amount,
@@ -519,7 +1333,6 @@ impl VestingContract {
             start_time,
             end_time,
             keeper_fee,
-            title: String::from_slice(&env, ""),
             is_initialized: true,
             is_irrevocable,
             creation_time: now,
@@ -527,26 +1340,21 @@ impl VestingContract {
             step_duration,
             staked_amount: 0,
             is_frozen: false,
-            title: String::from_slice(&env, ""),
         };
 
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_count), &vault);
+        Self::save_vault(env, vault_count, &vault);
+        Self::checkpoint_vault_yield(env, vault_count, &vault);
 
         let mut user_vaults: Vec<u64> = env
             .storage()
             .instance()
             .get(&DataKey::UserVaults(owner.clone()))
-            .unwrap_or(Vec::new(&env));
+            .unwrap_or(Vec::new(env));
         user_vaults.push_back(vault_count);
         env.storage()
             .instance()
             .set(&DataKey::UserVaults(owner.clone()), &user_vaults);
-
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultCount, &vault_count);
+        Self::sync_voting_power(env, &owner);
 
         env.storage()
             .instance()
@@ -569,10 +1377,10 @@ impl VestingContract {
             total_amount: amount,
             cliff_duration,
             start_time,
-            title: String::from_slice(&env, ""),
+            title: String::from_str(env, ""),
         };
         env.events().publish(
-            (Symbol::new(&env, "VaultCreated"), vault_count),
+            (Symbol::new(env, "VaultCreated"), vault_count),
             vault_created,
         );
 
@@ -592,7 +1400,8 @@ impl VestingContract {
         step_duration: u64,
     ) -> u64 {
         Self::require_admin(&env);
-        Self::require_valid_duration(start_time, end_time);
+        Self::require_valid_vault(&env, amount, start_time, end_time, keeper_fee, step_duration);
+        Self::require_token_capacity(&env, amount);
 
         let mut vault_count: u64 = env
             .storage()
@@ -619,7 +1428,7 @@ impl VestingContract {
         let is_irrevocable = !is_revocable;
 
         let vault = Vault {
-            title: String::from_slice(&env, ""),
+            title: String::from_str(&env, ""),
             owner: owner.clone(),
             delegate: None,
             total_amount: amount,
@@ -627,30 +1436,22 @@ impl VestingContract {
             start_time,
             end_time,
             keeper_fee,
-            is_initialized: false,
-            is_irrevocable,
-            title: String::from_slice(&env, ""),
             is_initialized: false, // Mark as lazy initialized
-            is_irrevocable: !is_revocable,
+            is_irrevocable,
             creation_time: now,
             is_transferable,
             step_duration,
             staked_amount: 0,
             is_frozen: false,
-            title: String::from_slice(&env, ""),
         };
 
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_count), &vault);
+        Self::save_vault(&env, vault_count, &vault);
+        Self::checkpoint_vault_yield(&env, vault_count, &vault);
 
         // Don't update user vaults list yet (lazy)
         env.storage()
             .instance()
             .set(&DataKey::VaultCount, &vault_count);
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultCount, &vault_count);
 
         let mut total_shares: i128 = env
             .storage()
@@ -669,7 +1470,7 @@ impl VestingContract {
             total_amount: amount,
             cliff_duration,
             start_time,
-            title: String::from_slice(&env, ""),
+            title: String::from_str(&env, ""),
         };
         env.events().publish(
             (Symbol::new(&env, "VaultCreated"), vault_count),
@@ -690,19 +1491,14 @@ impl VestingContract {
             return false;
         }
 
-        let vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let vault: Vault = Self::load_vault(env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         if !vault.is_initialized {
             let mut updated_vault = vault.clone();
             updated_vault.is_initialized = true;
 
-            env.storage()
-                .instance()
-                .set(&DataKey::VaultData(vault_id), &updated_vault);
+            Self::save_vault(env, vault_id, &updated_vault);
 
             let mut user_vaults: Vec<u64> = env
                 .storage()
@@ -712,7 +1508,8 @@ impl VestingContract {
             user_vaults.push_back(vault_id);
             env.storage()
                 .instance()
-                .set(&DataKey::UserVaults(updated_vault.owner), &user_vaults);
+                .set(&DataKey::UserVaults(updated_vault.owner.clone()), &user_vaults);
+            Self::sync_voting_power(env, &updated_vault.owner);
 
             true
         } else {
@@ -720,67 +1517,17 @@ impl VestingContract {
         }
     }
 
-    /// Helper functions for common time durations in seconds
-/// These can be used when creating vaults with periodic vesting
-impl VestingContract {
-    /// Convert days to seconds
-    pub const fn seconds(days: u64) -> u64 { days * 86400 }
-    
-    /// 30 days in seconds (monthly vesting)
-    pub const fn monthly() -> u64 { 30 * 86400 } // 2,592,000 seconds
-    
-    /// 90 days in seconds (quarterly vesting)  
-    pub const fn quarterly() -> u64 { 3 * 30 * 86400 } // 7,776,000 seconds
-    
-    /// 365 days in seconds (yearly vesting)
-    pub const fn yearly() -> u64 { 365 * 86400 } // 31,536,000 seconds
-}
-
-/// Calculate the amount of tokens that have vested based on time
-/// 
-/// Supports two vesting modes:
-/// 1. Linear vesting (step_duration = 0): Continuous vesting over time
-/// 2. Periodic vesting (step_duration > 0): Discrete step vesting with rounding down
-/// 
-/// For periodic vesting, the formula is:
-/// vested = (elapsed / step_duration) * (total_amount / total_steps) * step_duration
-/// This rounds down to the nearest completed step, ensuring users only receive
-/// tokens that have fully vested according to the step schedule.
-/// 
-/// # Arguments
-/// * `env` - The contract environment
-/// * `vault` - The vault to calculate vesting for
-/// 
-/// # Returns
-/// The amount of tokens that have vested
-fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
-        let now = env.ledger().timestamp();
-        if now < vault.start_time { return 0; }
-        if now >= vault.end_time { return vault.total_amount; }
-        
-        let duration = vault.end_time - vault.start_time;
-        if duration == 0 { return vault.total_amount; }
-        
-        let elapsed = now - vault.start_time;
-
-        if vault.step_duration > 0 {
-            // Periodic vesting with monthly rounding
-            // First, round elapsed time down to the nearest step boundary
-            let rounded_elapsed = (elapsed / vault.step_duration) * vault.step_duration;
-            
-            // Calculate completed steps
-            let completed_steps = rounded_elapsed / vault.step_duration;
-            
-            // Calculate rate per step: total_amount / total_steps
-            let total_steps = duration / vault.step_duration;
-            if total_steps == 0 { return 0; }
-            
-            let amount_per_step = vault.total_amount / total_steps as i128;
-            let vested = completed_steps as i128 * amount_per_step;
-    // Helper to calculate vested amount based on time (linear or step)
+    /// Calculate the amount of tokens that have vested based on time
+    ///
+    /// Supports two vesting modes:
+    /// 1. Linear vesting (step_duration = 0): Continuous vesting over time
+    /// 2. Periodic vesting (step_duration > 0): Discrete step vesting with rounding down
+    ///
+    /// For periodic vesting, elapsed time is rounded down to the nearest completed step,
+    /// ensuring users only receive tokens that have fully vested according to the step
+    /// schedule.
     fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         let now = env.ledger().timestamp();
-        if now < vault.start_time {
         if now <= vault.start_time {
             return 0;
         }
@@ -794,44 +1541,23 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         }
 
         let elapsed = now - vault.start_time;
-        let effective_elapsed = if vault.step_duration > 0 {
-            (elapsed / vault.step_duration) * vault.step_duration
-        } else {
-            elapsed
+        let effective_elapsed = match elapsed.checked_div(vault.step_duration) {
+            Some(steps) => steps * vault.step_duration,
+            None => elapsed,
         };
 
-        if vault.step_duration > 0 {
-            let completed_steps = elapsed / vault.step_duration;
-            let rate_per_second = vault.total_amount / duration as i128;
-            let vested = completed_steps as i128 * rate_per_second * vault.step_duration as i128;
-            
-            // Ensure we don't exceed total amount
-            vested.min(vault.total_amount)
-        } else {
-            // Linear vesting
-            (vault.total_amount * elapsed as i128) / duration as i128
-        }
-    }
-
-    // Keep original signature for existing logic to use current ledger time
-    fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
-        Self::calculate_time_vested_amount_at(vault, env.ledger().timestamp())
         (vault.total_amount * effective_elapsed as i128) / duration as i128
     }
 
-    // Claim tokens from vault
-    pub fn claim_tokens(env: Env, vault_id: u64, claim_amount: i128) -> i128 {
-        if Self::is_paused(env.clone()) {
-            panic!("Contract is paused");
+    // Claim tokens from vault and return the tokens paid out (principal plus accrued yield).
+    // Reverts if that would be less than `min_tokens_out`; see preview_claim.
+    pub fn claim_tokens(env: Env, vault_id: u64, claim_amount: i128, min_tokens_out: i128) -> i128 {
         // Check if contract is paused
         if Self::is_paused(env.clone()) {
             panic!("Contract is paused - all withdrawals are disabled");
         }
 
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         // Check if vault is frozen
@@ -846,53 +1572,12 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             panic!("Claim amount must be positive");
         }
 
-        let unlocked_amount =
-            if env.storage().instance().has(&DataKey::VaultMilestones(vault_id)) {
-                let milestones = Self::require_milestones_configured(&env, vault_id);
-                let unlocked_pct = Self::unlocked_percentage(&milestones);
-                Self::unlocked_amount(vault.total_amount, unlocked_pct)
-            } else {
-                Self::calculate_time_vested_amount(&env, &vault)
-            };
-
         vault.owner.require_auth();
 
-        let unlocked_amount = if env
-            .storage()
-            .instance()
-            .has(&DataKey::VaultMilestones(vault_id))
-        {
-            let milestones = Self::require_milestones_configured(&env, vault_id);
-            let unlocked_pct = Self::unlocked_percentage(&milestones);
-            Self::unlocked_amount(vault.total_amount, unlocked_pct)
-        } else {
-            Self::calculate_time_vested_amount(&env, &vault)
-        };
-
-        let liquid_balance = vault.total_amount - vault.released_amount - vault.staked_amount;
-        if claim_amount > liquid_balance {
-            let deficit = claim_amount - liquid_balance;
-
-            let staking_contract: Address = env
-                .storage()
-                .instance()
-                .get(&Symbol::new(&env, "StakingContract"))
-                .expect("Staking contract not set");
-
-            let args = vec![&env, vault_id.into_val(&env), deficit.into_val(&env)];
-            env.invoke_contract::<()>(&staking_contract, &Symbol::new(&env, "unstake"), args);
-
-            vault.staked_amount -= deficit;
+        let unlocked_amount = Self::vested_amount(&env, vault_id, &vault);
 
-            let mut total_staked: i128 = env
-                .storage()
-                .instance()
-                .get(&DataKey::TotalStaked)
-                .unwrap_or(0);
-            total_staked -= deficit;
-            env.storage()
-                .instance()
-                .set(&DataKey::TotalStaked, &total_staked);
+        if claim_amount > Self::internal_liquid_balance(&env, vault_id, &mut vault, claim_amount) {
+            panic!("Insufficient liquid balance - tokens are staked or unbonding");
         }
 
         let available_to_claim = unlocked_amount - vault.released_amount;
@@ -903,38 +1588,12 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             panic!("Insufficient unlocked tokens to claim");
         }
 
-        vault.released_amount += claim_amount;
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
-
-        // YIELD DISTRIBUTION - only vault-owned portion
         let token_client = Self::get_token_client(&env);
-        let current_balance = token_client.balance(&env.current_contract_address());
-        let admin_balance: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::AdminBalance)
-            .unwrap_or(0);
-
         let total_shares: i128 = env
             .storage()
             .instance()
             .get(&DataKey::TotalShares)
             .unwrap_or(0);
-        let total_staked: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalStaked)
-            .unwrap_or(0);
-        let liquid_shares = total_shares - total_staked;
-
-        let vault_portion = (current_balance - admin_balance).max(0);
-        let transfer_amount = if liquid_shares > 0 {
-            (claim_amount * vault_portion) / liquid_shares
-        } else {
-            claim_amount
-        };
 
         vault.released_amount += claim_amount;
         let mut updated_total_shares = total_shares;
@@ -942,17 +1601,21 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         env.storage()
             .instance()
             .set(&DataKey::TotalShares, &updated_total_shares);
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
+        Self::save_vault(&env, vault_id, &vault);
+        Self::sync_voting_power(&env, &vault.owner);
 
+        // Principal is paid 1:1; yield synced via sync_yield is paid on top
+        let transfer_amount = claim_amount + Self::take_accrued_yield(&env, vault_id, &vault);
+        if transfer_amount < min_tokens_out {
+            panic!("Tokens out below minimum");
+        }
         token_client.transfer(
             &env.current_contract_address(),
             &vault.owner,
             &transfer_amount,
         );
 
-        claim_amount
+        transfer_amount
     }
 
     /// Transfers the beneficiary role of a vault to a new address.
@@ -960,10 +1623,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
     pub fn transfer_beneficiary(env: Env, vault_id: u64, new_address: Address) {
         Self::require_admin(&env);
 
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         let old_owner = vault.owner.clone();
@@ -997,9 +1657,10 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         }
 
         vault.owner = new_address.clone();
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
+        Self::save_vault(&env, vault_id, &vault);
+
+        Self::sync_voting_power(&env, &old_owner);
+        Self::sync_voting_power(&env, &new_address);
 
         env.events().publish(
             (Symbol::new(&env, "BeneficiaryUpdated"), vault_id),
@@ -1009,10 +1670,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
 
     // Set delegate address for a vault (only owner can call)
     pub fn set_delegate(env: Env, vault_id: u64, delegate: Option<Address>) {
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         if !vault.is_initialized {
@@ -1024,29 +1682,27 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         let old_delegate = vault.delegate.clone();
 
         vault.delegate = delegate.clone();
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
+        Self::save_vault(&env, vault_id, &vault);
 
         env.events().publish(
             (Symbol::new(&env, "DelegateUpdated"), vault_id),
             (old_delegate, delegate),
         );
     }
-
+    // Claim tokens as delegate (tokens still go to owner); returns the tokens paid out
     // Claim tokens as delegate (tokens still go to owner)
-    pub fn claim_as_delegate(env: Env, vault_id: u64, claim_amount: i128) -> i128 {
-        if Self::is_paused(env.clone()) {
-            panic!("Contract is paused");
+    pub fn claim_as_delegate(
+        env: Env,
+        vault_id: u64,
+        claim_amount: i128,
+        min_tokens_out: i128,
+    ) -> i128 {
         // Check if contract is paused
         if Self::is_paused(env.clone()) {
             panic!("Contract is paused - all withdrawals are disabled");
         }
 
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         // Check if vault is frozen
@@ -1067,17 +1723,12 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             .unwrap_or_else(|| panic!("No delegate set for this vault"));
         delegate.require_auth();
 
-        let unlocked_amount = if env
-            .storage()
-            .instance()
-            .has(&DataKey::VaultMilestones(vault_id))
-        {
-            let milestones = Self::require_milestones_configured(&env, vault_id);
-            let unlocked_pct = Self::unlocked_percentage(&milestones);
-            Self::unlocked_amount(vault.total_amount, unlocked_pct)
-        } else {
-            Self::calculate_time_vested_amount(&env, &vault)
-        };
+        let unlocked_amount = Self::vested_amount(&env, vault_id, &vault);
+
+        if claim_amount > Self::internal_liquid_balance(&env, vault_id, &mut vault, claim_amount) {
+            panic!("Insufficient liquid balance - tokens are staked or unbonding");
+        }
+
         let available_to_claim = unlocked_amount - vault.released_amount;
         if available_to_claim <= 0 {
             panic!("No tokens available to claim");
@@ -1086,53 +1737,29 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             panic!("Insufficient unlocked tokens to claim");
         }
 
-        // YIELD DISTRIBUTION - only vault-owned portion
         let token_client = Self::get_token_client(&env);
-        let current_balance = token_client.balance(&env.current_contract_address());
-        let admin_balance: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::AdminBalance)
-            .unwrap_or(0);
-
         let total_shares: i128 = env
             .storage()
             .instance()
             .get(&DataKey::TotalShares)
             .unwrap_or(0);
-        let total_staked: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalStaked)
-            .unwrap_or(0);
-        let liquid_shares = total_shares - total_staked;
-
-        let vault_portion = (current_balance - admin_balance).max(0);
-        let transfer_amount = if liquid_shares > 0 {
-            (claim_amount * vault_portion) / liquid_shares
-        } else {
-            claim_amount
-        };
-
-        let mut updated_vault = vault.clone();
-        updated_vault.released_amount += claim_amount;
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &updated_vault);
 
-        claim_amount
+        vault.released_amount += claim_amount;
         let mut updated_total_shares = total_shares;
         updated_total_shares -= claim_amount;
         env.storage()
             .instance()
             .set(&DataKey::TotalShares, &updated_total_shares);
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &updated_vault);
+        Self::save_vault(&env, vault_id, &vault);
+        Self::sync_voting_power(&env, &vault.owner);
 
+        let transfer_amount = claim_amount + Self::take_accrued_yield(&env, vault_id, &vault);
+        if transfer_amount < min_tokens_out {
+            panic!("Tokens out below minimum");
+        }
         token_client.transfer(
             &env.current_contract_address(),
-            &updated_vault.owner,
+            &vault.owner,
             &transfer_amount,
         );
 
@@ -1142,10 +1769,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
     pub fn set_milestones(env: Env, vault_id: u64, milestones: Vec<Milestone>) {
         Self::require_admin(&env);
 
-        let vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
         if !vault.is_initialized {
             panic!("Vault not initialized");
@@ -1167,6 +1791,21 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             if seen.contains_key(m.id) {
                 panic!("Duplicate milestone id");
             }
+            if m.is_unlocked
+                || m.is_forfeited
+                || m.challenge_ends_at != 0
+                || m.unlocked_by.is_some()
+                || m.evidence_hash.is_some()
+            {
+                panic!("Milestones must be created locked");
+            }
+            if m.deadline != 0 && m.deadline <= env.ledger().timestamp() {
+                panic!("Milestone deadline must be in the future");
+            }
+            Self::require_valid_duration(&env, 0, m.vesting_duration);
+            if m.step_duration > m.vesting_duration {
+                panic!("Milestone step_duration exceeds vesting_duration");
+            }
             seen.set(m.id, true);
             total_pct = total_pct.saturating_add(m.percentage);
         }
@@ -1190,144 +1829,325 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             .unwrap_or(Vec::new(&env))
     }
 
-    pub fn unlock_milestone(env: Env, vault_id: u64, milestone_id: u64) {
-        Self::require_admin(&env);
-
-        let _vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+    // Unlock a milestone. Callable by the milestone's approver if one is set, otherwise
+    // by the admin. The evidence hash and unlocker are recorded on the milestone.
+    pub fn unlock_milestone(
+        env: Env,
+        vault_id: u64,
+        milestone_id: u64,
+        evidence_hash: BytesN<32>,
+    ) {
+        let _vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         let milestones = Self::require_milestones_configured(&env, vault_id);
 
-        let mut found = false;
+        let milestone = milestones
+            .iter()
+            .find(|m| m.id == milestone_id)
+            .unwrap_or_else(|| panic!("Milestone not found"));
+        if milestone.is_unlocked {
+            panic!("Milestone already unlocked");
+        }
+        if milestone.is_forfeited {
+            panic!("Milestone forfeited");
+        }
+        if milestone.deadline != 0 && env.ledger().timestamp() > milestone.deadline {
+            panic!("Milestone deadline passed");
+        }
+
+        let unlocked_by = match milestone.approver.clone() {
+            Some(approver) => {
+                approver.require_auth();
+                approver
+            }
+            None => {
+                Self::require_admin(&env);
+                Self::get_admin(env.clone())
+            }
+        };
+
+        let timestamp = env.ledger().timestamp();
+        let challenge_window: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MilestoneChallengeWindow)
+            .unwrap_or(0);
         let mut updated = Vec::new(&env);
         for m in milestones.iter() {
             if m.id == milestone_id {
-                found = true;
-                if m.is_unlocked {
-                    panic!("Milestone already unlocked");
-                }
                 updated.push_back(Milestone {
-                    id: m.id,
-                    percentage: m.percentage,
                     is_unlocked: true,
+                    evidence_hash: Some(evidence_hash.clone()),
+                    unlocked_by: Some(unlocked_by.clone()),
+                    unlocked_at: timestamp,
+                    challenge_ends_at: timestamp.saturating_add(challenge_window),
+                    ..m
                 });
             } else {
                 updated.push_back(m);
             }
         }
-        if !found {
-            panic!("Milestone not found");
-        }
 
         env.storage()
             .instance()
             .set(&DataKey::VaultMilestones(vault_id), &updated);
-        let timestamp = env.ledger().timestamp();
         env.events().publish(
             (Symbol::new(&env, "MilestoneUnlocked"), vault_id),
-            (milestone_id, timestamp),
+            (milestone_id, timestamp, unlocked_by, evidence_hash),
         );
     }
 
-    // Admin-only: set a short title for a vault (max 32 bytes)
-    pub fn set_vault_title(env: Env, vault_id: u64, title: String) {
+    // Admin-only: set the challenge window applied to future milestone unlocks
+    pub fn set_milestone_challenge_window(env: Env, window: u64) {
         Self::require_admin(&env);
-
-        // Enforce max length (32 bytes)
-        if title.len() > 32 {
-            panic!("Title too long");
-        }
-
-        let mut vault: Vault = env
-            .storage()
+        env.storage()
             .instance()
-            .get(&DataKey::VaultData(vault_id))
-            .unwrap_or_else(|| panic!("Vault not found"));
+            .set(&DataKey::MilestoneChallengeWindow, &window);
+    }
 
-        vault.title = title;
-        env.storage().instance().set(&DataKey::VaultData(vault_id), &vault);
+    // Get the milestone challenge window in seconds
+    pub fn get_milestone_challenge_window(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MilestoneChallengeWindow)
+            .unwrap_or(0)
     }
 
-    // Batch create vaults with lazy initialization
-    pub fn batch_create_vaults_lazy(env: Env, batch_data: BatchCreateData) -> Vec<u64> {
+    // Admin-only: designate (or clear) an arbiter who may re-lock milestones
+    pub fn set_milestone_arbiter(env: Env, arbiter: Option<Address>) {
         Self::require_admin(&env);
+        match arbiter {
+            Some(arbiter) => env
+                .storage()
+                .instance()
+                .set(&DataKey::MilestoneArbiter, &arbiter),
+            None => env.storage().instance().remove(&DataKey::MilestoneArbiter),
+        }
+    }
 
-        let mut vault_ids = Vec::new(&env);
-        let initial_count: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultCount)
-            .unwrap_or(0);
+    // Get the milestone arbiter (if any)
+    pub fn get_milestone_arbiter(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::MilestoneArbiter)
+    }
 
-        let mut vault_ids = Vec::new(&env);
-        let initial_count: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultCount)
-            .unwrap_or(0);
+    // Re-lock a milestone during its challenge window (admin or arbiter only).
+    // The milestone returns to its locked state and may be unlocked again later.
+    pub fn relock_milestone(
+        env: Env,
+        caller: Address,
+        vault_id: u64,
+        milestone_id: u64,
+        reason_code: u32,
+    ) {
+        let admin = Self::get_admin(env.clone());
+        let arbiter: Option<Address> = env.storage().instance().get(&DataKey::MilestoneArbiter);
+        if caller != admin && arbiter != Some(caller.clone()) {
+            panic!("Caller is not admin or arbiter");
+        }
+        caller.require_auth();
 
-        let total_amount: i128 = batch_data.amounts.iter().sum();
-        let mut admin_balance: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::AdminBalance)
-            .unwrap_or(0);
-        if admin_balance < total_amount {
-            panic!("Insufficient admin balance for batch");
+        let milestones = Self::require_milestones_configured(&env, vault_id);
+
+        let milestone = milestones
+            .iter()
+            .find(|m| m.id == milestone_id)
+            .unwrap_or_else(|| panic!("Milestone not found"));
+        if !milestone.is_unlocked {
+            panic!("Milestone not unlocked");
+        }
+        let now = env.ledger().timestamp();
+        if now >= milestone.challenge_ends_at {
+            panic!("Milestone unlock is final");
+        }
+
+        let mut updated = Vec::new(&env);
+        for m in milestones.iter() {
+            if m.id == milestone_id {
+                updated.push_back(Milestone {
+                    is_unlocked: false,
+                    evidence_hash: None,
+                    unlocked_by: None,
+                    unlocked_at: 0,
+                    challenge_ends_at: 0,
+                    ..m
+                });
+            } else {
+                updated.push_back(m);
+            }
         }
-        admin_balance -= total_amount;
         env.storage()
             .instance()
-            .set(&DataKey::AdminBalance, &admin_balance);
+            .set(&DataKey::VaultMilestones(vault_id), &updated);
 
-        let now = env.ledger().timestamp();
-        for i in 0..batch_data.recipients.len() {
-            let vault_id = initial_count + i as u64 + 1;
-            let start_time: u64 = batch_data.start_times.get(i).unwrap();
-            let end_time: u64 = batch_data.end_times.get(i).unwrap();
-            Self::require_valid_duration(start_time, end_time);
+        env.events().publish(
+            (Symbol::new(&env, "MilestoneRelocked"), vault_id),
+            (milestone_id, reason_code, caller, now),
+        );
+    }
 
-            let owner = batch_data.recipients.get(i).unwrap();
-            let amount = batch_data.amounts.get(i).unwrap();
-            let start_time = batch_data.start_times.get(i).unwrap();
-            let end_time = batch_data.end_times.get(i).unwrap();
-            let keeper_fee = batch_data.keeper_fees.get(i).unwrap();
+    // Forfeit a milestone whose deadline passed without an unlock. Anyone can call.
+    // Its share of total_amount is returned to the admin balance.
+    pub fn expire_milestone(env: Env, vault_id: u64, milestone_id: u64) -> i128 {
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
+            .unwrap_or_else(|| panic!("Vault not found"));
+
+        let milestones = Self::require_milestones_configured(&env, vault_id);
+
+        let milestone = milestones
+            .iter()
+            .find(|m| m.id == milestone_id)
+            .unwrap_or_else(|| panic!("Milestone not found"));
+        if milestone.is_unlocked {
+            panic!("Milestone already unlocked");
+        }
+        if milestone.is_forfeited {
+            panic!("Milestone already forfeited");
+        }
+        let now = env.ledger().timestamp();
+        if milestone.deadline == 0 || now <= milestone.deadline {
+            panic!("Milestone deadline not reached");
+        }
+
+        let forfeited = Self::unlocked_amount(vault.total_amount, milestone.percentage)
+            .min(vault.total_amount - vault.released_amount);
+        // The whole tranche must be returned, so staked or unbonding tokens block expiry
+        if forfeited > Self::internal_liquid_balance(&env, vault_id, &mut vault, forfeited) {
+            panic!("Insufficient liquid balance - tokens are staked or unbonding");
+        }
+
+        let mut updated = Vec::new(&env);
+        for m in milestones.iter() {
+            if m.id == milestone_id {
+                updated.push_back(Milestone {
+                    is_forfeited: true,
+                    ..m
+                });
+            } else {
+                updated.push_back(m);
+            }
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::VaultMilestones(vault_id), &updated);
+
+        vault.released_amount += forfeited;
+        Self::save_vault(&env, vault_id, &vault);
+        Self::sync_voting_power(&env, &vault.owner);
+        Self::checkpoint_vault_yield(&env, vault_id, &vault);
+
+        let mut admin_balance: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminBalance)
+            .unwrap_or(0);
+        admin_balance += Self::refund_funder(&env, vault_id, forfeited);
+        env.storage()
+            .instance()
+            .set(&DataKey::AdminBalance, &admin_balance);
+
+        let mut total_shares: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalShares)
+            .unwrap_or(0);
+        total_shares -= forfeited;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalShares, &total_shares);
+
+        env.events().publish(
+            (Symbol::new(&env, "MilestoneForfeited"), vault_id),
+            (milestone_id, forfeited, now),
+        );
+
+        forfeited
+    }
+
+    // Admin-only: set a short title for a vault (max 32 bytes)
+    pub fn set_vault_title(env: Env, vault_id: u64, title: String) {
+        Self::require_admin(&env);
+
+        // Enforce max length (32 bytes)
+        if title.len() > 32 {
+            panic!("Title too long");
+        }
+
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
+            .unwrap_or_else(|| panic!("Vault not found"));
+
+        vault.title = title;
+        Self::save_vault(&env, vault_id, &vault);
+    }
+
+    // Batch create vaults with lazy initialization
+    pub fn batch_create_vaults_lazy(env: Env, batch_data: BatchCreateData) -> Vec<u64> {
+        Self::require_admin(&env);
+        Self::require_valid_batch_size(&env, batch_data.recipients.len());
+
+        let mut vault_ids = Vec::new(&env);
+        let initial_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::VaultCount)
+            .unwrap_or(0);
+
+        let total_amount: i128 = batch_data.amounts.iter().sum();
+        Self::require_token_capacity(&env, total_amount);
+        let mut admin_balance: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminBalance)
+            .unwrap_or(0);
+        if admin_balance < total_amount {
+            panic!("Insufficient admin balance for batch");
+        }
+        admin_balance -= total_amount;
+        env.storage()
+            .instance()
+            .set(&DataKey::AdminBalance, &admin_balance);
+
+        let now = env.ledger().timestamp();
+        for i in 0..batch_data.recipients.len() {
+            let vault_id = initial_count + i as u64 + 1;
+            let start_time: u64 = batch_data.start_times.get(i).unwrap();
+            let end_time: u64 = batch_data.end_times.get(i).unwrap();
+            Self::require_valid_vault(
+                &env,
+                batch_data.amounts.get(i).unwrap(),
+                start_time,
+                end_time,
+                batch_data.keeper_fees.get(i).unwrap(),
+                batch_data.step_durations.get(i).unwrap_or(0),
+            );
+
+            let owner = batch_data.recipients.get(i).unwrap();
+            let amount = batch_data.amounts.get(i).unwrap();
+            let keeper_fee = batch_data.keeper_fees.get(i).unwrap();
             let step_duration = batch_data.step_durations.get(i).unwrap_or(0);
             let is_transferable = false;
             let is_irrevocable = false;
 
             let vault = Vault {
-                owner: owner.clone(),
-                title: String::from_slice(&env, ""),
-                owner: batch_data.recipients.get(i).unwrap(),
+                title: String::from_str(&env, ""),
+                owner,
                 delegate: None,
                 total_amount: amount,
                 released_amount: 0,
                 start_time,
                 end_time,
                 keeper_fee,
-                keeper_fee: batch_data.keeper_fees.get(i).unwrap(),
-                title: String::from_slice(&env, ""),
                 is_initialized: false, // Lazy initialization
-                is_irrevocable: false, // Default to revocable for batch operations
-                is_initialized: false,
-                is_irrevocable: false,
+                is_irrevocable,
                 creation_time: now,
                 step_duration,
-                is_initialized: false, // Lazy initialization
-                is_irrevocable,
                 is_transferable,
                 staked_amount: 0,
                 is_frozen: false,
-                title: String::from_slice(&env, ""),
             };
 
-            env.storage()
-                .instance()
-                .set(&DataKey::VaultData(vault_id), &vault);
+            Self::save_vault(&env, vault_id, &vault);
+            Self::checkpoint_vault_yield(&env, vault_id, &vault);
             vault_ids.push_back(vault_id);
 
             let cliff_duration = start_time.saturating_sub(now);
@@ -1337,7 +2157,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
                 total_amount: vault.total_amount,
                 cliff_duration,
                 start_time,
-                title: String::from_slice(&env, ""),
+                title: String::from_str(&env, ""),
             };
             env.events()
                 .publish((Symbol::new(&env, "VaultCreated"), vault_id), vault_created);
@@ -1364,6 +2184,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
     // Batch create vaults with full initialization
     pub fn batch_create_vaults_full(env: Env, batch_data: BatchCreateData) -> Vec<u64> {
         Self::require_admin(&env);
+        Self::require_valid_batch_size(&env, batch_data.recipients.len());
 
         let mut vault_ids = Vec::new(&env);
         let initial_count: u64 = env
@@ -1373,6 +2194,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             .unwrap_or(0);
 
         let total_amount: i128 = batch_data.amounts.iter().sum();
+        Self::require_token_capacity(&env, total_amount);
         let mut admin_balance: i128 = env
             .storage()
             .instance()
@@ -1391,10 +2213,17 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             let vault_id = initial_count + i as u64 + 1;
             let start_time: u64 = batch_data.start_times.get(i).unwrap();
             let end_time: u64 = batch_data.end_times.get(i).unwrap();
-            Self::require_valid_duration(start_time, end_time);
+            Self::require_valid_vault(
+                &env,
+                batch_data.amounts.get(i).unwrap(),
+                start_time,
+                end_time,
+                batch_data.keeper_fees.get(i).unwrap(),
+                batch_data.step_durations.get(i).unwrap_or(0),
+            );
 
             let vault = Vault {
-                title: String::from_slice(&env, ""),
+                title: String::from_str(&env, ""),
                 owner: batch_data.recipients.get(i).unwrap(),
                 delegate: None,
                 total_amount: batch_data.amounts.get(i).unwrap(),
@@ -1402,7 +2231,6 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
                 start_time,
                 end_time,
                 keeper_fee: batch_data.keeper_fees.get(i).unwrap(),
-                title: String::from_slice(&env, ""),
                 is_initialized: true,
                 is_irrevocable: false,
                 creation_time: now,
@@ -1410,12 +2238,10 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
                 step_duration: batch_data.step_durations.get(i).unwrap_or(0),
                 staked_amount: 0,
                 is_frozen: false,
-                title: String::from_slice(&env, ""),
             };
 
-            env.storage()
-                .instance()
-                .set(&DataKey::VaultData(vault_id), &vault);
+            Self::save_vault(&env, vault_id, &vault);
+            Self::checkpoint_vault_yield(&env, vault_id, &vault);
 
             let mut user_vaults: Vec<u64> = env
                 .storage()
@@ -1426,6 +2252,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             env.storage()
                 .instance()
                 .set(&DataKey::UserVaults(vault.owner.clone()), &user_vaults);
+            Self::sync_voting_power(&env, &vault.owner);
 
             vault_ids.push_back(vault_id);
 
@@ -1436,7 +2263,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
                 total_amount: vault.total_amount,
                 cliff_duration,
                 start_time,
-                title: String::from_slice(&env, ""),
+                title: String::from_str(&env, ""),
             };
             env.events()
                 .publish((Symbol::new(&env, "VaultCreated"), vault_id), vault_created);
@@ -1462,19 +2289,12 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
 
     // Get vault info (initializes if needed)
     pub fn get_vault(env: Env, vault_id: u64) -> Vault {
-        let vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         if !vault.is_initialized {
             Self::initialize_vault_metadata(&env, vault_id);
-            env.storage()
-                .instance()
-                .get(&DataKey::VaultData(vault_id))
-                .unwrap()
-                .unwrap_or_else(|| panic!("Vault not found"))
+            Self::load_vault(&env, vault_id).unwrap_or_else(|| panic!("Vault not found"))
         } else {
             vault
         }
@@ -1485,15 +2305,11 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         let vault_ids: Vec<u64> = env
             .storage()
             .instance()
-            .get(&DataKey::UserVaults(user.clone()))
             .get(&DataKey::UserVaults(user))
             .unwrap_or(Vec::new(&env));
 
         for vault_id in vault_ids.iter() {
-            let vault: Vault = env
-                .storage()
-                .instance()
-                .get(&DataKey::VaultData(vault_id))
+            let vault: Vault = Self::load_vault(&env, vault_id)
                 .unwrap_or_else(|| panic!("Vault not found"));
 
             if !vault.is_initialized {
@@ -1504,33 +2320,41 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         vault_ids
     }
 
-    // Admin-only: Revoke tokens from a vault and return them to admin
-    // Revoke tokens from a vault and return them to admin
-    // Internal helper: revoke full unreleased amount from a vault and emit event.
+    // Internal helper: revoke the unreleased amount of a vault that is held by this contract
+    // and emit event. Staked or unbonding tokens stay in the vault and can be revoked later.
     // Does NOT update admin balance — caller is responsible for a single aggregated transfer.
     fn internal_revoke_full(env: &Env, vault_id: u64) -> i128 {
-    pub fn revoke_tokens(env: Env, vault_id: u64) -> i128 {
-        Self::require_admin(&env);
-
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         if vault.is_irrevocable {
             panic!("Vault is irrevocable");
         }
 
-        let unreleased_amount = vault.total_amount - vault.released_amount;
-        if unreleased_amount <= 0 {
-        let returned = vault.total_amount - vault.released_amount;
-        if returned <= 0 {
+        let unreleased = vault.total_amount - vault.released_amount;
+        if unreleased <= 0 {
             panic!("No tokens available to revoke");
         }
+        let unreleased_amount =
+            unreleased.min(Self::internal_liquid_balance(env, vault_id, &mut vault, unreleased));
+        if unreleased_amount <= 0 {
+            panic!("Insufficient liquid balance - tokens are staked or unbonding");
+        }
 
-        vault.released_amount = vault.total_amount;
-        env.storage().instance().set(&DataKey::VaultData(vault_id), &vault);
+        vault.released_amount += unreleased_amount;
+        Self::save_vault(env, vault_id, &vault);
+        Self::sync_voting_power(env, &vault.owner);
+        Self::checkpoint_vault_yield(env, vault_id, &vault);
+
+        let mut total_shares: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalShares)
+            .unwrap_or(0);
+        total_shares -= unreleased_amount;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalShares, &total_shares);
 
         let timestamp = env.ledger().timestamp();
         env.events().publish(
@@ -1538,62 +2362,32 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             (unreleased_amount, timestamp),
         );
 
-        let mut total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap_or(0);
-        total_shares -= unreleased_amount;
-        env.storage().instance().set(&DataKey::TotalShares, &total_shares);
         unreleased_amount
     }
 
-
-    // Admin-only: Revoke tokens from a vault and return them to admin
+    // Admin-only: Revoke tokens from a vault and return them to admin or its funder
     pub fn revoke_tokens(env: Env, vault_id: u64) -> i128 {
         Self::require_admin(&env);
 
         let returned = Self::internal_revoke_full(&env, vault_id);
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
 
         let mut admin_balance: i128 = env
             .storage()
             .instance()
             .get(&DataKey::AdminBalance)
             .unwrap_or(0);
-        admin_balance += returned;
+        admin_balance += Self::refund_funder(&env, vault_id, returned);
         env.storage()
             .instance()
             .set(&DataKey::AdminBalance, &admin_balance);
 
-        let mut total_shares: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalShares)
-            .unwrap_or(0);
-        total_shares -= returned;
-        env.storage()
-            .instance()
-            .set(&DataKey::TotalShares, &total_shares);
-
-        let timestamp = env.ledger().timestamp();
-        env.events().publish(
-            (Symbol::new(&env, "TokensRevoked"), vault_id),
-            (unreleased_amount, timestamp),
-        );
-
-        unreleased_amount
         returned
     }
 
-    // Revoke a specific amount of tokens from a vault and return them to admin
+    // Revoke a specific amount of tokens from a vault and return them to admin or its funder
     pub fn revoke_partial(env: Env, vault_id: u64, amount: i128) -> i128 {
         Self::require_admin(&env);
 
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
-            .unwrap_or_else(|| panic!("Vault not found"));
-
         let returned = Self::internal_revoke_partial(&env, vault_id, amount);
 
         // Single admin balance update for this call
@@ -1602,7 +2396,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             .instance()
             .get(&DataKey::AdminBalance)
             .unwrap_or(0);
-        admin_balance += returned;
+        admin_balance += Self::refund_funder(&env, vault_id, returned);
         env.storage()
             .instance()
             .set(&DataKey::AdminBalance, &admin_balance);
@@ -1610,19 +2404,11 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         returned
     }
 
-    // Internal helper: revoke a specific amount from a vault and emit event.
+    // Internal helper: revoke up to `amount` from a vault, capped at what this contract holds
+    // for it, and emit event. Returns the amount revoked.
     // Does NOT update admin balance — caller is responsible for a single aggregated transfer.
     fn internal_revoke_partial(env: &Env, vault_id: u64, amount: i128) -> i128 {
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
-            .unwrap_or_else(|| panic!("Vault not found"));
-
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         if vault.is_irrevocable {
@@ -1636,24 +2422,15 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         if amount > unvested_balance {
             panic!("Amount exceeds unvested balance");
         }
+        let amount = amount.min(Self::internal_liquid_balance(env, vault_id, &mut vault, amount));
+        if amount <= 0 {
+            panic!("Insufficient liquid balance - tokens are staked or unbonding");
+        }
 
         vault.released_amount += amount;
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
-
-        let timestamp = env.ledger().timestamp();
-        env.events().publish(
-            (Symbol::new(env, "TokensRevoked"), vault_id),
-        let mut admin_balance: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::AdminBalance)
-            .unwrap_or(0);
-        admin_balance += amount;
-        env.storage()
-            .instance()
-            .set(&DataKey::AdminBalance, &admin_balance);
+        Self::save_vault(env, vault_id, &vault);
+        Self::sync_voting_power(env, &vault.owner);
+        Self::checkpoint_vault_yield(env, vault_id, &vault);
 
         let mut total_shares: i128 = env
             .storage()
@@ -1667,62 +2444,45 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
 
         let timestamp = env.ledger().timestamp();
         env.events().publish(
-            (Symbol::new(&env, "TokensRevoked"), vault_id),
+            (Symbol::new(env, "TokensRevoked"), vault_id),
             (amount, timestamp),
         );
 
-        let mut total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap_or(0);
-        total_shares -= amount;
-        env.storage().instance().set(&DataKey::TotalShares, &total_shares);
-
-        let timestamp = env.ledger().timestamp();
-        env.events().publish((Symbol::new(&env, "TokensRevoked"), vault_id), (amount, timestamp));
-
         amount
     }
 
     pub fn batch_revoke(env: Env, vault_ids: Vec<u64>) -> i128 {
         Self::require_admin(&env);
+        Self::require_valid_batch_size(&env, vault_ids.len());
 
         let mut total_returned: i128 = 0;
-        let mut total_shares_change: i128 = 0;
-        
-        for id in vault_ids.iter() {
-            let vault: Vault = env.storage().instance().get(&DataKey::VaultData(id)).unwrap_or_else(|| panic!("Vault not found"));
-            
-            if vault.is_irrevocable { panic!("Vault is irrevocable"); }
-            
-            let unreleased_amount = vault.total_amount - vault.released_amount;
-            if unreleased_amount > 0 {
-                total_returned += unreleased_amount;
-                total_shares_change += unreleased_amount;
-                
-                // Update vault to mark all tokens as released
-                let mut updated_vault = vault;
-                updated_vault.released_amount = vault.total_amount;
-                env.storage().instance().set(&DataKey::VaultData(id), &updated_vault);
-            }
+        let mut admin_returned: i128 = 0;
         for vault_id in vault_ids.iter() {
-            let mut vault: Vault = env
-                .storage()
-                .instance()
-                .get(&DataKey::VaultData(vault_id))
+            let mut vault: Vault = Self::load_vault(&env, vault_id)
                 .unwrap_or_else(|| panic!("Vault not found"));
 
             if vault.is_irrevocable {
                 panic!("Vault is irrevocable");
             }
 
-            let returned = vault.total_amount - vault.released_amount;
+            // Only tokens held by this contract can be returned now
+            let unreleased = vault.total_amount - vault.released_amount;
+            let returned = if unreleased > 0 {
+                unreleased.min(Self::internal_liquid_balance(&env, vault_id, &mut vault, unreleased))
+            } else {
+                0
+            };
             if returned <= 0 {
+                Self::save_vault(&env, vault_id, &vault);
                 continue;
             }
 
-            vault.released_amount = vault.total_amount;
-            env.storage()
-                .instance()
-                .set(&DataKey::VaultData(vault_id), &vault);
+            vault.released_amount += returned;
+            Self::save_vault(&env, vault_id, &vault);
+            Self::sync_voting_power(&env, &vault.owner);
+            Self::checkpoint_vault_yield(&env, vault_id, &vault);
             total_returned += returned;
+            admin_returned += Self::refund_funder(&env, vault_id, returned);
 
             let timestamp = env.ledger().timestamp();
             env.events().publish(
@@ -1736,15 +2496,10 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             .instance()
             .get(&DataKey::AdminBalance)
             .unwrap_or(0);
-        admin_balance += total_returned;
+        admin_balance += admin_returned;
         env.storage()
             .instance()
             .set(&DataKey::AdminBalance, &admin_balance);
-            
-        // Update total shares
-        let mut total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap_or(0);
-        total_shares -= total_shares_change;
-        env.storage().instance().set(&DataKey::TotalShares, &total_shares);
 
         let mut total_shares: i128 = env
             .storage()
@@ -1765,18 +2520,15 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         total_returned
     }
 
-    // Clawback a vault within the grace period (1 hour)
+    // Clawback a vault within the policy's clawback_grace_period of its creation
     pub fn clawback_vault(env: Env, vault_id: u64) -> i128 {
         Self::require_admin(&env);
 
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         let now = env.ledger().timestamp();
-        let grace_period = 3600u64;
+        let grace_period = Self::policy(&env).clawback_grace_period;
 
         if now > vault.creation_time + grace_period {
             panic!("Grace period expired");
@@ -1784,10 +2536,9 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         if vault.released_amount > 0 {
             panic!("Tokens already claimed");
         }
-
-
-        if vault.released_amount > 0 {
-            panic!("Tokens already claimed");
+        let total = vault.total_amount;
+        if total > Self::internal_liquid_balance(&env, vault_id, &mut vault, total) {
+            panic!("Insufficient liquid balance - tokens are staked or unbonding");
         }
 
         let mut admin_balance: i128 = env
@@ -1795,15 +2546,15 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             .instance()
             .get(&DataKey::AdminBalance)
             .unwrap_or(0);
-        admin_balance += vault.total_amount;
+        admin_balance += Self::refund_funder(&env, vault_id, vault.total_amount);
         env.storage()
             .instance()
             .set(&DataKey::AdminBalance, &admin_balance);
 
         vault.released_amount = vault.total_amount;
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
+        Self::save_vault(&env, vault_id, &vault);
+        Self::sync_voting_power(&env, &vault.owner);
+        Self::checkpoint_vault_yield(&env, vault_id, &vault);
 
         let mut total_shares: i128 = env
             .storage()
@@ -1825,10 +2576,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
 
     // Transfer vault ownership to another beneficiary (if transferable)
     pub fn transfer_vault(env: Env, vault_id: u64, new_beneficiary: Address) {
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         if !vault.is_initialized {
@@ -1857,10 +2605,6 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         env.storage()
             .instance()
             .set(&DataKey::UserVaults(old_owner.clone()), &new_old_user_vaults);
-        env.storage().instance().set(
-            &DataKey::UserVaults(old_owner.clone()),
-            &new_old_user_vaults,
-        );
 
         let mut new_user_vaults: Vec<u64> = env
             .storage()
@@ -1871,16 +2615,13 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         env.storage()
             .instance()
             .set(&DataKey::UserVaults(new_beneficiary.clone()), &new_user_vaults);
-        env.storage().instance().set(
-            &DataKey::UserVaults(new_beneficiary.clone()),
-            &new_user_vaults,
-        );
 
         vault.owner = new_beneficiary.clone();
         vault.delegate = None;
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
+        Self::save_vault(&env, vault_id, &vault);
+
+        Self::sync_voting_power(&env, &old_owner);
+        Self::sync_voting_power(&env, &new_beneficiary);
 
         env.events().publish(
             (Symbol::new(&env, "BeneficiaryUpdated"), vault_id),
@@ -1890,10 +2631,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
 
     // Rotate beneficiary key (security feature, allows self-transfer even if non-transferable)
     pub fn rotate_beneficiary_key(env: Env, vault_id: u64, new_address: Address) {
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         if !vault.is_initialized {
@@ -1919,10 +2657,6 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         env.storage()
             .instance()
             .set(&DataKey::UserVaults(old_owner.clone()), &new_old_user_vaults);
-        env.storage().instance().set(
-            &DataKey::UserVaults(old_owner.clone()),
-            &new_old_user_vaults,
-        );
 
         let mut new_user_vaults: Vec<u64> = env
             .storage()
@@ -1936,9 +2670,10 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
 
         vault.owner = new_address.clone();
         vault.delegate = None;
-        env.storage()
-            .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
+        Self::save_vault(&env, vault_id, &vault);
+
+        Self::sync_voting_power(&env, &old_owner);
+        Self::sync_voting_power(&env, &new_address);
 
         env.events().publish(
             (Symbol::new(&env, "BeneficiaryRotated"), vault_id),
@@ -1946,7 +2681,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         );
     }
 
-    // Set the whitelisted staking contract address
+    // Set the whitelisted staking contract address (must implement StakingAdapter)
     pub fn set_staking_contract(env: Env, contract: Address) {
         Self::require_admin(&env);
         env.storage()
@@ -1954,12 +2689,25 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             .set(&Symbol::new(&env, "StakingContract"), &contract);
     }
 
-    // Stake unvested tokens to the whitelisted staking contract
-    pub fn stake_tokens(env: Env, vault_id: u64, amount: i128, validator: Address) {
-        let mut vault: Vault = env
+    // Get the registered staking contract address (if any)
+    pub fn get_staking_contract(env: Env) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, "StakingContract"))
+    }
+
+    fn get_staking_client(env: &Env) -> StakingAdapterClient<'_> {
+        let staking_contract: Address = env
             .storage()
             .instance()
-            .get(&DataKey::VaultData(vault_id))
+            .get(&Symbol::new(env, "StakingContract"))
+            .expect("Staking contract not set");
+        StakingAdapterClient::new(env, &staking_contract)
+    }
+
+    // Stake unvested tokens to the whitelisted staking contract
+    pub fn stake_tokens(env: Env, vault_id: u64, amount: i128, validator: Address) {
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         if !vault.is_initialized {
@@ -1968,7 +2716,10 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
 
         vault.owner.require_auth();
 
-        let available = vault.total_amount - vault.released_amount - vault.staked_amount;
+        let available = vault.total_amount
+            - vault.released_amount
+            - vault.staked_amount
+            - Self::unbonding_amount(&env, vault_id);
         if amount <= 0 {
             panic!("Amount must be positive");
         }
@@ -1976,19 +2727,21 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             panic!("Insufficient funds to stake");
         }
 
-        let staking_contract: Address = env
-            .storage()
-            .instance()
-            .get(&Symbol::new(&env, "StakingContract"))
-            .expect("Staking contract not set");
+        // A vault delegates to a single validator at a time
+        if let Some(current) = Self::get_vault_validator(env.clone(), vault_id) {
+            if current != validator {
+                panic!("Vault is staked with a different validator");
+            }
+        }
 
-        let args = vec![
-            &env,
-            vault_id.into_val(&env),
-            amount.into_val(&env),
-            validator.into_val(&env),
-        ];
-        env.invoke_contract::<()>(&staking_contract, &Symbol::new(&env, "stake"), args);
+        // The adapter stakes tokens it holds, so hand them over before calling stake
+        let staking_client = Self::get_staking_client(&env);
+        Self::get_token_client(&env).transfer(
+            &env.current_contract_address(),
+            &staking_client.address,
+            &amount,
+        );
+        staking_client.stake(&vault_id, &amount, &validator);
 
         vault.staked_amount += amount;
 
@@ -2002,111 +2755,951 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             .instance()
             .set(&DataKey::TotalStaked, &total_staked);
 
+        Self::save_vault(&env, vault_id, &vault);
         env.storage()
             .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
-    }
+            .set(&DataKey::VaultValidator(vault_id), &validator);
 
-    // Mark a vault as irrevocable to prevent admin withdrawal
-    pub fn mark_irrevocable(env: Env, vault_id: u64) {
-        Self::require_admin(&env);
+        env.events().publish(
+            (Symbol::new(&env, "TokensStaked"), vault_id),
+            (amount, validator, env.ledger().timestamp()),
+        );
+    }
 
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+    // Start unbonding staked tokens (only owner can call). The tokens return to the
+    // vault's liquid balance once complete_unbonding runs after the unbonding period.
+    pub fn unstake_tokens(env: Env, vault_id: u64, amount: i128) {
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
-        if vault.is_irrevocable {
-            panic!("Vault is already irrevocable");
+        if !vault.is_initialized {
+            panic!("Vault not initialized");
         }
 
-        vault.is_irrevocable = true;
-        env.storage()
+        vault.owner.require_auth();
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        if amount > vault.staked_amount {
+            panic!("Amount exceeds staked balance");
+        }
+
+        Self::internal_unstake(&env, vault_id, &mut vault, amount);
+        Self::save_vault(&env, vault_id, &vault);
+    }
+
+    // Get the validator a vault is currently staked with (if any)
+    pub fn get_vault_validator(env: Env, vault_id: u64) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::VaultValidator(vault_id))
+    }
+
+    // Report a staking loss (e.g. validator slashing) for a vault. Callable by the
+    // registered staking contract or the admin. The lost principal is removed from
+    // the vault and from TotalShares/TotalStaked so claims no longer assume it exists.
+    // Unbonding tokens are still slashable, so the loss is split across the staked
+    // amount and the unbonding requests in proportion to their size.
+    pub fn report_stake_loss(env: Env, reporter: Address, vault_id: u64, loss: i128) {
+        let admin = Self::get_admin(env.clone());
+        if reporter != admin && Self::get_staking_contract(env.clone()) != Some(reporter.clone()) {
+            panic!("Reporter is not staking contract or admin");
+        }
+        reporter.require_auth();
+
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
+            .unwrap_or_else(|| panic!("Vault not found"));
+
+        if loss <= 0 {
+            panic!("Loss must be positive");
+        }
+        let unbonding = Self::unbonding_amount(&env, vault_id);
+        let at_risk = vault.staked_amount + unbonding;
+        if loss > at_risk {
+            panic!("Loss exceeds staked and unbonding amount");
+        }
+
+        let staked_loss = loss * vault.staked_amount / at_risk;
+        if loss > staked_loss {
+            Self::slash_unbonding(&env, vault_id, loss - staked_loss, unbonding);
+        }
+        vault.staked_amount -= staked_loss;
+        vault.total_amount -= loss;
+        Self::save_vault(&env, vault_id, &vault);
+        Self::sync_voting_power(&env, &vault.owner);
+        Self::checkpoint_vault_yield(&env, vault_id, &vault);
+        if vault.staked_amount == 0 {
+            env.storage()
+                .instance()
+                .remove(&DataKey::VaultValidator(vault_id));
+        }
+
+        let mut total_staked: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalStaked)
+            .unwrap_or(0);
+        total_staked -= loss;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalStaked, &total_staked);
+
+        let mut total_shares: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalShares)
+            .unwrap_or(0);
+        total_shares -= loss;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalShares, &total_shares);
+
+        env.events().publish(
+            (Symbol::new(&env, "StakeSlashed"), vault_id),
+            (loss, reporter, env.ledger().timestamp()),
+        );
+    }
+
+    // Internal helper: take `loss` out of a vault's unbonding requests in proportion to
+    // their amounts. Rounding leftovers go to the first requests that still have room.
+    fn slash_unbonding(env: &Env, vault_id: u64, loss: i128, unbonding: i128) {
+        let queue = Self::get_unbonding_requests(env.clone(), vault_id);
+        let mut remaining = loss;
+        let mut cut = Vec::new(env);
+        for request in queue.iter() {
+            let share = loss * request.amount / unbonding;
+            remaining -= share;
+            cut.push_back(UnbondingRequest {
+                amount: request.amount - share,
+                maturity: request.maturity,
+            });
+        }
+
+        let mut slashed = Vec::new(env);
+        for mut request in cut.iter() {
+            let extra = remaining.min(request.amount);
+            request.amount -= extra;
+            remaining -= extra;
+            if request.amount > 0 {
+                slashed.push_back(request);
+            }
+        }
+
+        if slashed.is_empty() {
+            env.storage()
+                .instance()
+                .remove(&DataKey::UnbondingQueue(vault_id));
+        } else {
+            env.storage()
+                .instance()
+                .set(&DataKey::UnbondingQueue(vault_id), &slashed);
+        }
+    }
+
+    // Choose whether harvested rewards are immediately claimable or vest with the vault
+    // (only owner can call)
+    pub fn set_reward_compounding(env: Env, vault_id: u64, compound: bool) {
+        let vault: Vault = Self::load_vault(&env, vault_id)
+            .unwrap_or_else(|| panic!("Vault not found"));
+
+        vault.owner.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::CompoundRewards(vault_id), &compound);
+    }
+
+    // Pull accrued rewards for a vault from the staking contract. Anyone can call;
+    // rewards are always credited to the vault's owner.
+    pub fn harvest_staking_rewards(env: Env, vault_id: u64) -> i128 {
+        let vault: Vault = Self::load_vault(&env, vault_id)
+            .unwrap_or_else(|| panic!("Vault not found"));
+
+        if !vault.is_initialized {
+            panic!("Vault not initialized");
+        }
+
+        // Credit what actually arrived rather than what the adapter reports
+        let token_client = Self::get_token_client(&env);
+        let balance_before = token_client.balance(&env.current_contract_address());
+        Self::get_staking_client(&env).claim_rewards(&vault_id);
+        let harvested = token_client.balance(&env.current_contract_address()) - balance_before;
+        if harvested <= 0 {
+            return 0;
+        }
+
+        let compound: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::CompoundRewards(vault_id))
+            .unwrap_or(false);
+
+        let mut rewards = Self::get_staking_rewards(env.clone(), vault_id);
+        if compound {
+            rewards.compounded += harvested;
+        } else {
+            rewards.claimable += harvested;
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::StakingRewards(vault_id), &rewards);
+
+        let total_rewards = Self::staking_reward_liabilities(&env) + harvested;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalStakingRewards, &total_rewards);
+
+        env.events().publish(
+            (Symbol::new(&env, "RewardsHarvested"), vault_id),
+            (harvested, compound),
+        );
+
+        harvested
+    }
+
+    // Withdraw harvested rewards: all immediately claimable rewards plus the vested
+    // share of compounded rewards (only owner can call)
+    pub fn claim_staking_rewards(env: Env, vault_id: u64) -> i128 {
+        if Self::is_paused(env.clone()) {
+            panic!("Contract is paused - all withdrawals are disabled");
+        }
+
+        let vault: Vault = Self::load_vault(&env, vault_id)
+            .unwrap_or_else(|| panic!("Vault not found"));
+
+        if vault.is_frozen {
+            panic!("Vault is frozen - claims are disabled");
+        }
+
+        vault.owner.require_auth();
+
+        let mut rewards = Self::get_staking_rewards(env.clone(), vault_id);
+        let vested = Self::vested_amount(&env, vault_id, &vault);
+        let compounded_vested = if vault.total_amount > 0 {
+            (rewards.compounded * vested.min(vault.total_amount)) / vault.total_amount
+        } else {
+            rewards.compounded
+        };
+        let compounded_payable = (compounded_vested - rewards.compounded_released).max(0);
+
+        let payout = rewards.claimable + compounded_payable;
+        if payout <= 0 {
+            panic!("No rewards available to claim");
+        }
+
+        rewards.claimable = 0;
+        rewards.compounded_released += compounded_payable;
+        env.storage()
+            .instance()
+            .set(&DataKey::StakingRewards(vault_id), &rewards);
+
+        let total_rewards = Self::staking_reward_liabilities(&env) - payout;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalStakingRewards, &total_rewards);
+
+        Self::get_token_client(&env).transfer(
+            &env.current_contract_address(),
+            &vault.owner,
+            &payout,
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "RewardsClaimed"), vault_id),
+            (vault.owner, payout),
+        );
+
+        payout
+    }
+
+    // Get harvested staking rewards for a vault
+    pub fn get_staking_rewards(env: Env, vault_id: u64) -> StakingRewards {
+        env.storage()
+            .instance()
+            .get(&DataKey::StakingRewards(vault_id))
+            .unwrap_or(StakingRewards {
+                claimable: 0,
+                compounded: 0,
+                compounded_released: 0,
+            })
+    }
+
+    // Reward tokens held by the contract on behalf of beneficiaries; excluded from
+    // the surplus distributed by sync_yield
+    fn staking_reward_liabilities(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalStakingRewards)
+            .unwrap_or(0)
+    }
+
+    // Admin-only: set the unbonding period applied to new unstake requests
+    pub fn set_unbonding_period(env: Env, period: u64) {
+        Self::require_admin(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::UnbondingPeriod, &period);
+    }
+
+    // Get the unbonding period in seconds
+    pub fn get_unbonding_period(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::UnbondingPeriod)
+            .unwrap_or(0)
+    }
+
+    // Get pending unbonding requests for a vault
+    pub fn get_unbonding_requests(env: Env, vault_id: u64) -> Vec<UnbondingRequest> {
+        env.storage()
+            .instance()
+            .get(&DataKey::UnbondingQueue(vault_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // Withdraw all matured unbonding requests of a vault back into its liquid balance.
+    // Anyone can call; returns the amount that became liquid.
+    pub fn complete_unbonding(env: Env, vault_id: u64) -> i128 {
+        if !env.storage().instance().has(&DataKey::VaultData(vault_id)) {
+            panic!("Vault not found");
+        }
+        Self::internal_complete_unbonding(&env, vault_id)
+    }
+
+    fn unbonding_amount(env: &Env, vault_id: u64) -> i128 {
+        let queue: Vec<UnbondingRequest> = env
+            .storage()
+            .instance()
+            .get(&DataKey::UnbondingQueue(vault_id))
+            .unwrap_or(Vec::new(env));
+        let mut total: i128 = 0;
+        for request in queue.iter() {
+            total += request.amount;
+        }
+        total
+    }
+
+    // Internal helper: ask the adapter to unstake and queue the request until maturity.
+    // Does NOT persist the vault — caller is responsible for saving it.
+    fn internal_unstake(env: &Env, vault_id: u64, vault: &mut Vault, amount: i128) {
+        Self::get_staking_client(env).unstake(&vault_id, &amount);
+
+        vault.staked_amount -= amount;
+
+        let maturity = env
+            .ledger()
+            .timestamp()
+            .saturating_add(Self::get_unbonding_period(env.clone()));
+        let mut queue = Self::get_unbonding_requests(env.clone(), vault_id);
+        queue.push_back(UnbondingRequest { amount, maturity });
+        env.storage()
+            .instance()
+            .set(&DataKey::UnbondingQueue(vault_id), &queue);
+
+        let validator = Self::get_vault_validator(env.clone(), vault_id);
+        if vault.staked_amount == 0 {
+            env.storage()
+                .instance()
+                .remove(&DataKey::VaultValidator(vault_id));
+        }
+
+        env.events().publish(
+            (Symbol::new(env, "TokensUnstaked"), vault_id),
+            (amount, validator, maturity),
+        );
+    }
+
+    // Internal helper: withdraw matured requests from the adapter. Their shares stop
+    // counting as TotalStaked once the tokens are back in the contract.
+    fn internal_complete_unbonding(env: &Env, vault_id: u64) -> i128 {
+        let now = env.ledger().timestamp();
+        let queue = Self::get_unbonding_requests(env.clone(), vault_id);
+
+        let mut matured: i128 = 0;
+        let mut pending = Vec::new(env);
+        for request in queue.iter() {
+            if request.maturity <= now {
+                matured += request.amount;
+            } else {
+                pending.push_back(request);
+            }
+        }
+        if matured == 0 {
+            return 0;
+        }
+
+        Self::get_staking_client(env).withdraw(&vault_id, &matured);
+
+        if pending.is_empty() {
+            env.storage()
+                .instance()
+                .remove(&DataKey::UnbondingQueue(vault_id));
+        } else {
+            env.storage()
+                .instance()
+                .set(&DataKey::UnbondingQueue(vault_id), &pending);
+        }
+
+        let mut total_staked: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalStaked)
+            .unwrap_or(0);
+        total_staked -= matured;
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalStaked, &total_staked);
+
+        env.events().publish(
+            (Symbol::new(env, "UnbondingCompleted"), vault_id),
+            (matured, now),
+        );
+
+        matured
+    }
+
+    // Internal helper: a vault's liquid principal, after completing its matured unbonding
+    // and, without an unbonding period, synchronously unstaking the shortfall for `amount`.
+    // Does NOT persist the vault — caller is responsible for saving it.
+    fn internal_liquid_balance(env: &Env, vault_id: u64, vault: &mut Vault, amount: i128) -> i128 {
+        let mut liquid_balance = vault.total_amount
+            - vault.released_amount
+            - vault.staked_amount
+            - Self::unbonding_amount(env, vault_id);
+        if amount > liquid_balance {
+            liquid_balance += Self::internal_complete_unbonding(env, vault_id);
+        }
+        if amount > liquid_balance && Self::get_unbonding_period(env.clone()) == 0 {
+            let deficit = (amount - liquid_balance).min(vault.staked_amount);
+            if deficit > 0 {
+                Self::internal_unstake(env, vault_id, vault, deficit);
+                liquid_balance += Self::internal_complete_unbonding(env, vault_id);
+            }
+        }
+        liquid_balance
+    }
+
+    // Mark a vault as irrevocable to prevent admin withdrawal
+    pub fn mark_irrevocable(env: Env, vault_id: u64) {
+        Self::require_admin(&env);
+
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
+            .unwrap_or_else(|| panic!("Vault not found"));
+
+        if vault.is_irrevocable {
+            panic!("Vault is already irrevocable");
+        }
+
+        vault.is_irrevocable = true;
+        Self::save_vault(&env, vault_id, &vault);
+
+        let timestamp = env.ledger().timestamp();
+        env.events().publish(
+            (Symbol::new(&env, "IrrevocableMarked"), vault_id),
+            timestamp,
+        );
+    }
+
+    // Check if a vault is irrevocable
+    pub fn is_vault_irrevocable(env: Env, vault_id: u64) -> bool {
+        let vault: Vault = Self::load_vault(&env, vault_id)
+            .unwrap_or_else(|| panic!("Vault not found"));
+
+        vault.is_irrevocable
+    }
+
+    // Get contract state for invariant checking
+    pub fn get_contract_state(env: Env) -> (i128, i128, i128) {
+        let admin_balance: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminBalance)
+            .unwrap_or(0);
+
+        let vault_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::VaultCount)
+            .unwrap_or(0);
+        let mut total_locked = 0i128;
+        let mut total_claimed = 0i128;
+
+        for i in 1..=vault_count {
+            if let Some(vault) = Self::load_vault(&env, i) {
+                total_locked += vault.total_amount - vault.released_amount;
+                total_claimed += vault.released_amount;
+            }
+        }
+
+        (total_locked, total_claimed, admin_balance)
+    }
+
+    // Check invariant: Total Locked + Admin Balance + Tokens Paid Out = Initial Supply
+    // Tokens paid out = total_claimed minus any that were revoked (returned to admin_balance).
+    // Simplest correct form: total_locked + admin_balance <= initial_supply
+    // and total_locked + admin_balance + net_paid_out == initial_supply.
+    // We verify: admin_balance + total_locked == initial_supply - net_distributed
+    // The safe checkable invariant: total_locked + admin_balance must never exceed initial_supply,
+    // and (initial_supply - admin_balance - total_locked) must be non-negative (tokens claimed out).
+    pub fn check_invariant(env: Env) -> bool {
+        let initial_supply: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::InitialSupply)
+            .unwrap_or(0);
+        let (total_locked, _total_claimed, admin_balance) = Self::get_contract_state(env);
+
+        // All tokens are either: locked in vaults, held by admin, or paid out to beneficiaries.
+        // locked + admin_balance must never exceed initial_supply (no tokens created from nothing).
+        // initial_supply - locked - admin_balance = net tokens paid out (must be >= 0).
+        let net_paid_out = initial_supply - total_locked - admin_balance;
+        net_paid_out >= 0
+    }
+
+    // Compare the contract's actual token balances with what it owes. The vesting token is
+    // checked against every internal counter; other whitelisted tokens carry no liabilities.
+    pub fn reconcile(env: Env) -> Vec<TokenReconciliation> {
+        let mut reports = Vec::new(&env);
+        let main_token: Option<Address> = env.storage().instance().get(&DataKey::Token);
+        if main_token.is_some() {
+            reports.push_back(Self::internal_reconcile(&env));
+        }
+
+        for token_address in Self::get_whitelisted_tokens(env.clone()).iter() {
+            if main_token.as_ref() == Some(&token_address) {
+                continue;
+            }
+            let balance =
+                token::Client::new(&env, &token_address).balance(&env.current_contract_address());
+            reports.push_back(TokenReconciliation {
+                token: token_address,
+                balance,
+                vault_liabilities: 0,
+                staked: 0,
+                admin_balance: 0,
+                staking_rewards: 0,
+                outstanding_yield: 0,
+                keeper_fees: 0,
+                surplus: balance,
+            });
+        }
+
+        reports
+    }
+
+    // Credit the vesting token's verified surplus to AdminBalance (admin only). Surplus on
+    // the vesting token is yield owed to vault shares, so it is distributed via sync_yield
+    // first; only what the index cannot represent (or all of it when no shares exist) is left.
+    pub fn sync_admin_balance(env: Env) -> i128 {
+        Self::require_admin(&env);
+
+        Self::sync_yield(env.clone());
+        let surplus = Self::internal_reconcile(&env).surplus;
+        if surplus <= 0 {
+            panic!("No surplus to sync");
+        }
+
+        let admin_balance: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminBalance)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::AdminBalance, &(admin_balance + surplus));
+        let initial_supply: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::InitialSupply)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::InitialSupply, &(initial_supply + surplus));
+
+        env.events().publish(
+            (Symbol::new(&env, "AdminBalanceSynced"),),
+            (surplus, admin_balance + surplus),
+        );
+
+        surplus
+    }
+
+    // Internal helper: reconcile the vesting token's balance against internal liabilities.
+    // Keeper fees are transferred at claim time, so they are reported but never owed.
+    fn internal_reconcile(env: &Env) -> TokenReconciliation {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .unwrap_or_else(|| panic!("Token not set"));
+        let vault_liabilities: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalShares)
+            .unwrap_or(0);
+        let staked: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalStaked)
+            .unwrap_or(0);
+        let admin_balance: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminBalance)
+            .unwrap_or(0);
+        let outstanding_yield: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::OutstandingYield)
+            .unwrap_or(0);
+        let staking_rewards = Self::staking_reward_liabilities(env);
+        let keeper_fees: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&DataKey::KeeperFees)
+            .unwrap_or(Map::new(env));
+        let mut total_keeper_fees: i128 = 0;
+        for fee in keeper_fees.values().iter() {
+            total_keeper_fees += fee;
+        }
+
+        let balance = token::Client::new(env, &token).balance(&env.current_contract_address());
+        // Staked and unbonding tokens are held by the staking contract, not here
+        let surplus = balance
+            - (vault_liabilities - staked)
+            - admin_balance
+            - staking_rewards
+            - outstanding_yield;
+
+        TokenReconciliation {
+            token,
+            balance,
+            vault_liabilities,
+            staked,
+            admin_balance,
+            staking_rewards,
+            outstanding_yield,
+            keeper_fees: total_keeper_fees,
+            surplus,
+        }
+    }
+
+    // --- Yield ---
+
+    // Distribute tokens held above all known liabilities to vault shares by bumping the
+    // yield-per-share index. Anyone can call; returns the amount distributed.
+    pub fn sync_yield(env: Env) -> i128 {
+        let total_shares: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalShares)
+            .unwrap_or(0);
+        if total_shares <= 0 {
+            return 0;
+        }
+
+        let outstanding: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::OutstandingYield)
+            .unwrap_or(0);
+
+        let surplus = Self::internal_reconcile(&env).surplus;
+        if surplus <= 0 {
+            return 0;
+        }
+
+        // Only recognize what the index can represent; rounding dust stays as surplus
+        let index_delta = (surplus * YIELD_INDEX_SCALE) / total_shares;
+        let distributed = (index_delta * total_shares) / YIELD_INDEX_SCALE;
+        if distributed <= 0 {
+            return 0;
+        }
+
+        let index = Self::yield_index(&env) + index_delta;
+        env.storage().instance().set(&DataKey::YieldIndex, &index);
+        env.storage()
+            .instance()
+            .set(&DataKey::OutstandingYield, &(outstanding + distributed));
+
+        env.events().publish(
+            (Symbol::new(&env, "YieldSynced"),),
+            (distributed, index, env.ledger().timestamp()),
+        );
+
+        distributed
+    }
+
+    // Yield accrued by a vault as of the last sync_yield
+    pub fn get_accrued_yield(env: Env, vault_id: u64) -> i128 {
+        if !env.storage().instance().has(&DataKey::VaultData(vault_id)) {
+            panic!("Vault not found");
+        }
+        let state = Self::vault_yield_state(&env, vault_id);
+        state.accrued + Self::pending_yield(&env, &state)
+    }
+
+    // Exact token amount a claim of `shares` would pay out right now: principal 1:1
+    // plus all yield accrued by the vault
+    pub fn preview_claim(env: Env, vault_id: u64, shares: i128) -> i128 {
+        if shares < 0 {
+            panic!("Shares must be non-negative");
+        }
+        shares + Self::get_accrued_yield(env, vault_id)
+    }
+
+    // Withdraw a vault's accrued yield without claiming principal (only owner can call)
+    pub fn claim_yield(env: Env, vault_id: u64) -> i128 {
+        if Self::is_paused(env.clone()) {
+            panic!("Contract is paused - all withdrawals are disabled");
+        }
+
+        let vault: Vault = Self::load_vault(&env, vault_id)
+            .unwrap_or_else(|| panic!("Vault not found"));
+
+        if vault.is_frozen {
+            panic!("Vault is frozen - claims are disabled");
+        }
+
+        vault.owner.require_auth();
+
+        let amount = Self::take_accrued_yield(&env, vault_id, &vault);
+        if amount <= 0 {
+            panic!("No yield available to claim");
+        }
+
+        Self::get_token_client(&env).transfer(
+            &env.current_contract_address(),
+            &vault.owner,
+            &amount,
+        );
+
+        amount
+    }
+
+    fn yield_index(env: &Env) -> i128 {
+        env.storage()
             .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
+            .get(&DataKey::YieldIndex)
+            .unwrap_or(0)
+    }
+
+    fn vault_yield_state(env: &Env, vault_id: u64) -> VaultYieldState {
+        env.storage()
+            .instance()
+            .get(&DataKey::VaultYield(vault_id))
+            .unwrap_or(VaultYieldState {
+                shares: 0,
+                index: Self::yield_index(env),
+                accrued: 0,
+            })
+    }
+
+    fn pending_yield(env: &Env, state: &VaultYieldState) -> i128 {
+        (state.shares * (Self::yield_index(env) - state.index)) / YIELD_INDEX_SCALE
+    }
+
+    // Accrue yield for the vault's previous share balance and record its current one.
+    // Must be called after every change to a vault's unreleased balance.
+    fn checkpoint_vault_yield(env: &Env, vault_id: u64, vault: &Vault) -> VaultYieldState {
+        let mut state = Self::vault_yield_state(env, vault_id);
+        state.accrued += Self::pending_yield(env, &state);
+        state.index = Self::yield_index(env);
+        state.shares = (vault.total_amount - vault.released_amount).max(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::VaultYield(vault_id), &state);
+        state
+    }
+
+    // Internal helper: checkpoint and zero a vault's accrued yield, returning it.
+    // Does NOT transfer — caller is responsible for paying it out.
+    fn take_accrued_yield(env: &Env, vault_id: u64, vault: &Vault) -> i128 {
+        let mut state = Self::checkpoint_vault_yield(env, vault_id, vault);
+        let amount = state.accrued;
+        if amount <= 0 {
+            return 0;
+        }
+
+        state.accrued = 0;
+        env.storage()
+            .instance()
+            .set(&DataKey::VaultYield(vault_id), &state);
+
+        let outstanding: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::OutstandingYield)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::OutstandingYield, &(outstanding - amount));
 
-        let timestamp = env.ledger().timestamp();
         env.events().publish(
-            (Symbol::new(&env, "IrrevocableMarked"), vault_id),
-            timestamp,
+            (Symbol::new(env, "YieldPaid"), vault_id),
+            (vault.owner.clone(), amount),
         );
+
+        amount
     }
 
-    // Check if a vault is irrevocable
-    pub fn is_vault_irrevocable(env: Env, vault_id: u64) -> bool {
-        let vault: Vault = env
+    // --- Governance ---
+
+    // Current voting power of an address: unvested (total - released) balance of the
+    // vaults of every beneficiary delegating to it
+    pub fn get_votes(env: Env, account: Address) -> i128 {
+        let checkpoints: Vec<VotesCheckpoint> = env
             .storage()
             .instance()
-            .get(&DataKey::VaultData(vault_id))
-            .unwrap_or_else(|| panic!("Vault not found"));
+            .get(&DataKey::VoteCheckpoints(account))
+            .unwrap_or(Vec::new(&env));
+        checkpoints.last().map(|c| c.votes).unwrap_or(0)
+    }
 
-        vault.is_irrevocable
+    // Voting power of an address at a past ledger timestamp
+    pub fn get_past_votes(env: Env, account: Address, timestamp: u64) -> i128 {
+        if timestamp >= env.ledger().timestamp() {
+            panic!("Timestamp not yet finalized");
+        }
+
+        let checkpoints: Vec<VotesCheckpoint> = env
+            .storage()
+            .instance()
+            .get(&DataKey::VoteCheckpoints(account))
+            .unwrap_or(Vec::new(&env));
+
+        // Binary search for the last checkpoint at or before `timestamp`
+        let mut low: u32 = 0;
+        let mut high: u32 = checkpoints.len();
+        while low < high {
+            let mid = (low + high) / 2;
+            if checkpoints.get(mid).unwrap().timestamp > timestamp {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        if low == 0 {
+            0
+        } else {
+            checkpoints.get(low - 1).unwrap().votes
+        }
     }
 
-    // Get contract state for invariant checking
-    pub fn get_contract_state(env: Env) -> (i128, i128, i128) {
-        let admin_balance: i128 = env
+    // Delegate a beneficiary's voting power to another address. Independent of the
+    // per-vault claim delegate; pass your own address to take the votes back.
+    pub fn delegate_votes(env: Env, delegator: Address, delegatee: Address) {
+        delegator.require_auth();
+
+        let previous = Self::get_vote_delegate(env.clone(), delegator.clone());
+        if previous == delegatee {
+            return;
+        }
+
+        if delegatee == delegator {
+            env.storage()
+                .instance()
+                .remove(&DataKey::VoteDelegate(delegator.clone()));
+        } else {
+            env.storage()
+                .instance()
+                .set(&DataKey::VoteDelegate(delegator.clone()), &delegatee);
+        }
+
+        let locked: i128 = env
             .storage()
             .instance()
-            .get(&DataKey::AdminBalance)
+            .get(&DataKey::LockedBalance(delegator.clone()))
             .unwrap_or(0);
+        if locked != 0 {
+            Self::write_votes_checkpoint(&env, &previous, -locked);
+            Self::write_votes_checkpoint(&env, &delegatee, locked);
+        }
 
-        let vault_count: u64 = env
+        env.events().publish(
+            (Symbol::new(&env, "DelegateChanged"), delegator),
+            (previous, delegatee),
+        );
+    }
+
+    // Get the address a beneficiary's votes are delegated to
+    pub fn get_vote_delegate(env: Env, account: Address) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::VoteDelegate(account.clone()))
+            .unwrap_or(account)
+    }
+
+    // Recompute an owner's unvested balance across UserVaults and checkpoint the change.
+    // Lazily created vaults count once they are initialized into UserVaults.
+    fn sync_voting_power(env: &Env, owner: &Address) {
+        let vault_ids: Vec<u64> = env
             .storage()
             .instance()
-            .get(&DataKey::VaultCount)
-            .unwrap_or(0);
-        let mut total_locked = 0i128;
-        let mut total_claimed = 0i128;
+            .get(&DataKey::UserVaults(owner.clone()))
+            .unwrap_or(Vec::new(env));
 
-        for i in 1..=vault_count {
-            if let Some(vault) = env
-                .storage()
-                .instance()
-                .get::<DataKey, Vault>(&DataKey::VaultData(i))
-            {
-                total_locked += vault.total_amount - vault.released_amount;
-                total_claimed += vault.released_amount;
+        let mut locked: i128 = 0;
+        for vault_id in vault_ids.iter() {
+            if let Some(vault) = Self::load_vault(env, vault_id) {
+                locked += (vault.total_amount - vault.released_amount).max(0);
             }
         }
 
-        (total_locked, total_claimed, admin_balance)
+        let previous: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LockedBalance(owner.clone()))
+            .unwrap_or(0);
+        if locked == previous {
+            return;
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::LockedBalance(owner.clone()), &locked);
+
+        let delegatee = Self::get_vote_delegate(env.clone(), owner.clone());
+        Self::write_votes_checkpoint(env, &delegatee, locked - previous);
     }
 
-    // Check invariant: Total Locked + Admin Balance + Tokens Paid Out = Initial Supply
-    // Tokens paid out = total_claimed minus any that were revoked (returned to admin_balance).
-    // Simplest correct form: total_locked + admin_balance <= initial_supply
-    // and total_locked + admin_balance + net_paid_out == initial_supply.
-    // We verify: admin_balance + total_locked == initial_supply - net_distributed
-    // The safe checkable invariant: total_locked + admin_balance must never exceed initial_supply,
-    // and (initial_supply - admin_balance - total_locked) must be non-negative (tokens claimed out).
-    pub fn check_invariant(env: Env) -> bool {
-        let initial_supply: i128 = env
+    fn write_votes_checkpoint(env: &Env, account: &Address, delta: i128) {
+        let mut checkpoints: Vec<VotesCheckpoint> = env
             .storage()
             .instance()
-            .get(&DataKey::InitialSupply)
-            .unwrap_or(0);
-        let (total_locked, _total_claimed, admin_balance) = Self::get_contract_state(env);
+            .get(&DataKey::VoteCheckpoints(account.clone()))
+            .unwrap_or(Vec::new(env));
 
-        // All tokens are either: locked in vaults, held by admin, or paid out to beneficiaries.
-        // locked + admin_balance must never exceed initial_supply (no tokens created from nothing).
-        // initial_supply - locked - admin_balance = net tokens paid out (must be >= 0).
-        let net_paid_out = initial_supply - total_locked - admin_balance;
-        net_paid_out >= 0
+        let now = env.ledger().timestamp();
+        let previous = checkpoints.last().map(|c| c.votes).unwrap_or(0);
+        let checkpoint = VotesCheckpoint {
+            timestamp: now,
+            votes: previous + delta,
+        };
+        match checkpoints.last() {
+            Some(last) if last.timestamp == now => {
+                checkpoints.set(checkpoints.len() - 1, checkpoint);
+            }
+            _ => checkpoints.push_back(checkpoint),
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::VoteCheckpoints(account.clone()), &checkpoints);
+
+        env.events().publish(
+            (Symbol::new(env, "DelegateVotesChanged"), account.clone()),
+            (previous, previous + delta),
+        );
     }
 
     // --- Auto-Claim Logic ---
 
     // Calculate currently claimable tokens based on linear vesting
     pub fn get_claimable_amount(env: Env, vault_id: u64) -> i128 {
-        let vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
-        let vested = Self::calculate_time_vested_amount(&env, &vault);
+        let vested = Self::vested_amount(&env, vault_id, &vault);
 
         if vested > vault.released_amount {
             vested - vault.released_amount
@@ -2122,10 +3715,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
             panic!("Contract is paused - all withdrawals are disabled");
         }
 
-        let mut vault: Vault = env
-            .storage()
-            .instance()
-            .get(&DataKey::VaultData(vault_id))
+        let mut vault: Vault = Self::load_vault(&env, vault_id)
             .unwrap_or_else(|| panic!("Vault not found"));
 
         // Check if vault is frozen
@@ -2139,51 +3729,117 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
 
         let claimable = Self::get_claimable_amount(env.clone(), vault_id);
 
-        
         // Ensure there's enough to cover the fee and something left for beneficiary
         if claimable <= vault.keeper_fee {
             panic!("Insufficient claimable tokens to cover fee");
         }
 
-        let beneficiary_amount = claimable - vault.keeper_fee;
-        let keeper_fee = vault.keeper_fee;
+        if !Self::auto_claim_preferences_met(&env, vault_id, claimable) {
+            panic!("Auto-claim not allowed by beneficiary preferences");
+        }
 
-        vault.released_amount += claimable;
-        env.storage().instance().set(&DataKey::VaultData(vault_id), &vault);
+        if claimable > Self::internal_liquid_balance(&env, vault_id, &mut vault, claimable) {
+            panic!("Insufficient liquid balance - tokens are staked or unbonding");
+        }
+
+        let (_beneficiary_amount, keeper_fee) =
+            Self::internal_auto_claim(&env, vault_id, vault, claimable, &keeper);
 
-        // YIELD DISTRIBUTION - only vault-owned portion
         let token_client = Self::get_token_client(&env);
-        let current_balance = token_client.balance(&env.current_contract_address());
-        let admin_balance: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::AdminBalance)
-            .unwrap_or(0);
+        token_client.transfer(&env.current_contract_address(), &keeper, &keeper_fee);
+
+        Self::credit_keeper_fees(&env, &keeper, keeper_fee);
+    }
+
+    // Batch auto-claim for keepers. Ineligible vaults (unknown, frozen, uninitialized,
+    // claimable <= keeper_fee, blocked by preferences, or staked/unbonding) are skipped
+    // instead of aborting the whole batch, and the keeper is paid with a single aggregated
+    // transfer at the end.
+    pub fn auto_claim_many(env: Env, vault_ids: Vec<u64>, keeper: Address) -> Vec<AutoClaimResult> {
+        if Self::is_paused(env.clone()) {
+            panic!("Contract is paused - all withdrawals are disabled");
+        }
+        Self::require_valid_batch_size(&env, vault_ids.len());
+
+        let mut results: Vec<AutoClaimResult> = Vec::new(&env);
+        let mut total_keeper_fee: i128 = 0;
+
+        for vault_id in vault_ids.iter() {
+            let vault: Option<Vault> = Self::load_vault(&env, vault_id);
+
+            let claimable = match &vault {
+                Some(vault) if !vault.is_frozen && vault.is_initialized => {
+                    Self::get_claimable_amount(env.clone(), vault_id)
+                }
+                _ => 0,
+            };
+
+            let eligible = vault.filter(|vault| {
+                claimable > vault.keeper_fee
+                    && Self::auto_claim_preferences_met(&env, vault_id, claimable)
+            });
+            let Some(vault) = eligible.and_then(|mut vault| {
+                let liquid = Self::internal_liquid_balance(&env, vault_id, &mut vault, claimable);
+                if claimable > liquid {
+                    // Keep whatever unbonding was completed while checking
+                    Self::save_vault(&env, vault_id, &vault);
+                    return None;
+                }
+                Some(vault)
+            }) else {
+                results.push_back(AutoClaimResult {
+                    vault_id,
+                    claimed: false,
+                    beneficiary_amount: 0,
+                    keeper_fee: 0,
+                });
+                continue;
+            };
+
+            let (beneficiary_amount, keeper_fee) =
+                Self::internal_auto_claim(&env, vault_id, vault, claimable, &keeper);
+            total_keeper_fee += keeper_fee;
+
+            results.push_back(AutoClaimResult {
+                vault_id,
+                claimed: true,
+                beneficiary_amount,
+                keeper_fee,
+            });
+        }
+
+        if total_keeper_fee > 0 {
+            let token_client = Self::get_token_client(&env);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &keeper,
+                &total_keeper_fee,
+            );
+            Self::credit_keeper_fees(&env, &keeper, total_keeper_fee);
+        }
+
+        results
+    }
+
+    // Internal helper: release the full claimable amount of an eligible vault and pay
+    // the beneficiary (plus any accrued yield). Returns (beneficiary_amount, keeper_fee).
+    // Does NOT pay the keeper — caller is responsible for the keeper transfer.
+    fn internal_auto_claim(
+        env: &Env,
+        vault_id: u64,
+        mut vault: Vault,
+        claimable: i128,
+        keeper: &Address,
+    ) -> (i128, i128) {
+        let beneficiary_amount = claimable - vault.keeper_fee;
+        let keeper_fee = vault.keeper_fee;
 
+        let token_client = Self::get_token_client(env);
         let total_shares: i128 = env
             .storage()
             .instance()
             .get(&DataKey::TotalShares)
             .unwrap_or(0);
-        let total_staked: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalStaked)
-            .unwrap_or(0);
-        let liquid_shares = total_shares - total_staked;
-
-        let vault_portion = (current_balance - admin_balance).max(0);
-
-        let beneficiary_tokens = if liquid_shares > 0 {
-            (beneficiary_amount * vault_portion) / liquid_shares
-        } else {
-            beneficiary_amount
-        };
-        let keeper_tokens = if liquid_shares > 0 {
-            (keeper_fee * vault_portion) / liquid_shares
-        } else {
-            keeper_fee
-        };
 
         vault.released_amount += claimable;
         let mut updated_total_shares = total_shares;
@@ -2191,34 +3847,147 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
         env.storage()
             .instance()
             .set(&DataKey::TotalShares, &updated_total_shares);
+        Self::save_vault(env, vault_id, &vault);
+        Self::sync_voting_power(env, &vault.owner);
         env.storage()
             .instance()
-            .set(&DataKey::VaultData(vault_id), &vault);
+            .set(&DataKey::LastAutoClaim(vault_id), &env.ledger().timestamp());
+
+        // Keeper is paid the fee 1:1; all accrued yield goes to the beneficiary
+        let beneficiary_tokens =
+            beneficiary_amount + Self::take_accrued_yield(env, vault_id, &vault);
 
         token_client.transfer(
             &env.current_contract_address(),
             &vault.owner,
             &beneficiary_tokens,
         );
-        token_client.transfer(&env.current_contract_address(), &keeper, &keeper_tokens);
 
-        let mut fees: Map<Address, i128> = env
-            .storage()
-            .instance()
-            .get(&DataKey::KeeperFees)
-            .unwrap_or(Map::new(&env));
-        let current_fees = fees.get(keeper.clone()).unwrap_or(0);
-        fees.set(keeper.clone(), current_fees + keeper_fee);
+        env.events().publish(
+            (Symbol::new(env, "KeeperClaim"), vault_id),
+            (keeper.clone(), beneficiary_amount, keeper_fee),
+        );
+
+        (beneficiary_amount, keeper_fee)
+    }
+
+    // Set auto-claim preferences for a vault (only owner can call)
+    pub fn set_auto_claim_preferences(env: Env, vault_id: u64, prefs: AutoClaimPreferences) {
+        let vault: Vault = Self::load_vault(&env, vault_id)
+            .unwrap_or_else(|| panic!("Vault not found"));
+
+        if !vault.is_initialized {
+            panic!("Vault not initialized");
+        }
+
+        vault.owner.require_auth();
+
+        if prefs.min_claim_amount < 0 {
+            panic!("Minimum claim amount must be non-negative");
+        }
+
         env.storage()
             .instance()
-            .set(&DataKey::KeeperFees, &fees);
+            .set(&DataKey::AutoClaimPrefs(vault_id), &prefs);
 
         env.events().publish(
-            (Symbol::new(&env, "KeeperClaim"), vault_id),
-            (keeper, beneficiary_amount, keeper_fee),
+            (Symbol::new(&env, "AutoClaimPrefsUpdated"), vault_id),
+            (prefs.min_claim_amount, prefs.min_interval, prefs.opted_out),
         );
     }
 
+    // Get auto-claim preferences for a vault (defaults allow any claim above the fee)
+    pub fn get_auto_claim_preferences(env: Env, vault_id: u64) -> AutoClaimPreferences {
+        env.storage()
+            .instance()
+            .get(&DataKey::AutoClaimPrefs(vault_id))
+            .unwrap_or(AutoClaimPreferences {
+                min_claim_amount: 0,
+                min_interval: 0,
+                opted_out: false,
+            })
+    }
+
+    // Check whether a keeper could successfully auto_claim this vault right now
+    pub fn is_auto_claim_eligible(env: Env, vault_id: u64) -> bool {
+        if Self::is_paused(env.clone()) {
+            return false;
+        }
+
+        let vault: Vault = Self::load_vault(&env, vault_id)
+            .unwrap_or_else(|| panic!("Vault not found"));
+
+        if vault.is_frozen || !vault.is_initialized {
+            return false;
+        }
+
+        let claimable = Self::get_claimable_amount(env.clone(), vault_id);
+        claimable > vault.keeper_fee && Self::auto_claim_preferences_met(&env, vault_id, claimable)
+    }
+
+    // List vault ids eligible for auto_claim, scanning at most `limit` ids from `start_id`
+    pub fn get_auto_claim_eligible_vaults(env: Env, start_id: u64, limit: u32) -> Vec<u64> {
+        let vault_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::VaultCount)
+            .unwrap_or(0);
+
+        let mut eligible = Vec::new(&env);
+        let first = start_id.max(1);
+        let last = first.saturating_add(limit as u64).min(vault_count + 1);
+        for vault_id in first..last {
+            if env.storage().instance().has(&DataKey::VaultData(vault_id))
+                && Self::is_auto_claim_eligible(env.clone(), vault_id)
+            {
+                eligible.push_back(vault_id);
+            }
+        }
+        eligible
+    }
+
+    // Check the beneficiary's auto-claim preferences against the current claimable amount
+    fn auto_claim_preferences_met(env: &Env, vault_id: u64, claimable: i128) -> bool {
+        let prefs: AutoClaimPreferences = match env
+            .storage()
+            .instance()
+            .get(&DataKey::AutoClaimPrefs(vault_id))
+        {
+            Some(prefs) => prefs,
+            None => return true,
+        };
+
+        if prefs.opted_out || claimable < prefs.min_claim_amount {
+            return false;
+        }
+
+        if prefs.min_interval > 0 {
+            if let Some(last) = env
+                .storage()
+                .instance()
+                .get::<_, u64>(&DataKey::LastAutoClaim(vault_id))
+            {
+                if env.ledger().timestamp() < last.saturating_add(prefs.min_interval) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    // Add to the running fee total tracked for a keeper
+    fn credit_keeper_fees(env: &Env, keeper: &Address, amount: i128) {
+        let mut fees: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&DataKey::KeeperFees)
+            .unwrap_or(Map::new(env));
+        let current_fees = fees.get(keeper.clone()).unwrap_or(0);
+        fees.set(keeper.clone(), current_fees + amount);
+        env.storage().instance().set(&DataKey::KeeperFees, &fees);
+    }
+
     // Get accumulated fees for a keeper
     pub fn get_keeper_fee(env: Env, keeper: Address) -> i128 {
         let fees: Map<Address, i128> = env
@@ -2256,11 +4025,7 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
 
         let mut total_liabilities: i128 = 0;
         for i in 1..=vault_count {
-            if let Some(vault) = env
-                .storage()
-                .instance()
-                .get::<DataKey, Vault>(&DataKey::VaultData(i))
-            {
+            if let Some(vault) = Self::load_vault(&env, i) {
                 let unreleased = vault.total_amount - vault.released_amount;
                 if unreleased > 0 {
                     total_liabilities += unreleased;
@@ -2297,5 +4062,3 @@ fn calculate_time_vested_amount(env: &Env, vault: &Vault) -> i128 {
 
 // Unit tests for this contract are kept as integration tests under
 // `contracts/vesting_contracts/tests/` to avoid `no_std` test-harness friction.
-// mod test;
-// mod test; // Disabled - tests need refactoring
@@ -0,0 +1,25 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Interface a staking contract must implement to be registered via
+/// `VestingContract::set_staking_contract`.
+///
+/// Amounts are denominated in vault shares and keyed by vault id, so a single
+/// adapter can serve every vault of a vesting contract.
+#[contractclient(name = "StakingAdapterClient")]
+pub trait StakingAdapter {
+    /// Stake `amount` on behalf of `vault_id` with the given validator. The vesting
+    /// contract transfers `amount` to the adapter right before this call.
+    fn stake(env: Env, vault_id: u64, amount: i128, validator: Address);
+
+    /// Start unbonding `amount` previously staked for `vault_id`
+    fn unstake(env: Env, vault_id: u64, amount: i128);
+
+    /// Return `amount` of matured unbonded tokens for `vault_id` to the caller
+    fn withdraw(env: Env, vault_id: u64, amount: i128);
+
+    /// Rewards accrued for `vault_id` that have not been claimed yet
+    fn pending_rewards(env: Env, vault_id: u64) -> i128;
+
+    /// Transfer accrued rewards for `vault_id` to the caller, returning the amount
+    fn claim_rewards(env: Env, vault_id: u64) -> i128;
+}
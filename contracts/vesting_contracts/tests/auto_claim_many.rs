@@ -0,0 +1,119 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    token, vec, Address, Env,
+};
+
+use vesting_contracts::VestingContractClient;
+
+mod common;
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, Address) {
+    let (client, _admin, token_addr) = common::setup(env);
+
+    (client, token_addr)
+}
+
+#[test]
+fn auto_claim_many_skips_ineligible_and_pays_keeper_once() {
+    let env = Env::default();
+    let (client, token_addr) = setup(&env);
+    let token_client = token::Client::new(&env, &token_addr);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let keeper = Address::generate(&env);
+
+    let a = common::create_vault(&env, &client, &alice, 10);
+    let b = common::create_vault(&env, &client, &bob, 10);
+    // Fee larger than anything that will be claimable
+    let c = common::create_vault(&env, &client, &carol, 5_000);
+    client.freeze_vault(&b);
+
+    env.ledger().with_mut(|li| li.timestamp += 500);
+
+    let results = client.auto_claim_many(&vec![&env, a, b, c], &keeper);
+    assert_eq!(results.len(), 3);
+
+    let ra = results.get(0).unwrap();
+    assert!(ra.claimed);
+    assert_eq!(ra.beneficiary_amount, 490);
+    assert_eq!(ra.keeper_fee, 10);
+
+    assert!(!results.get(1).unwrap().claimed);
+    assert!(!results.get(2).unwrap().claimed);
+
+    assert_eq!(token_client.balance(&alice), 490);
+    assert_eq!(token_client.balance(&bob), 0);
+    assert_eq!(token_client.balance(&keeper), 10);
+    assert_eq!(client.get_keeper_fee(&keeper), 10);
+}
+
+#[test]
+fn auto_claim_many_aggregates_keeper_fees() {
+    let env = Env::default();
+    let (client, token_addr) = setup(&env);
+    let token_client = token::Client::new(&env, &token_addr);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let a = common::create_vault(&env, &client, &alice, 7);
+    let b = common::create_vault(&env, &client, &bob, 3);
+
+    env.ledger().with_mut(|li| li.timestamp += 1_000);
+
+    let results = client.auto_claim_many(&vec![&env, a, b], &keeper);
+    assert!(results.get(0).unwrap().claimed);
+    assert!(results.get(1).unwrap().claimed);
+    assert_eq!(results.get(0).unwrap().beneficiary_amount, 993);
+    assert_eq!(results.get(1).unwrap().beneficiary_amount, 997);
+
+    // Each beneficiary gets exactly its vault minus its own fee
+    assert_eq!(token_client.balance(&alice), 993);
+    assert_eq!(token_client.balance(&bob), 997);
+    assert_eq!(token_client.balance(&keeper), 10);
+    assert_eq!(client.get_keeper_fee(&keeper), 10);
+
+    // Nothing was paid out beyond the two vaults
+    assert_eq!(token_client.balance(&client.address), 1_000_000 - 2_000);
+}
+
+#[test]
+fn auto_claim_many_pays_each_vault_exactly_and_skips_unknown_ids() {
+    let env = Env::default();
+    let (client, token_addr) = setup(&env);
+    let token_client = token::Client::new(&env, &token_addr);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let keeper = Address::generate(&env);
+
+    let a = common::create_vault(&env, &client, &alice, 20);
+    let b = common::create_vault(&env, &client, &bob, 30);
+    let c = common::create_vault(&env, &client, &carol, 40);
+
+    env.ledger().with_mut(|li| li.timestamp += 600);
+
+    // The unknown id sits between valid vaults and must not abort the batch
+    let results = client.auto_claim_many(&vec![&env, a, 999u64, b, c], &keeper);
+    assert_eq!(results.len(), 4);
+
+    let unknown = results.get(1).unwrap();
+    assert_eq!(unknown.vault_id, 999);
+    assert!(!unknown.claimed);
+    assert_eq!(unknown.beneficiary_amount, 0);
+
+    // Later vaults are not inflated by keeper tokens still held for earlier ones
+    assert_eq!(results.get(0).unwrap().beneficiary_amount, 580);
+    assert_eq!(results.get(2).unwrap().beneficiary_amount, 570);
+    assert_eq!(results.get(3).unwrap().beneficiary_amount, 560);
+
+    assert_eq!(token_client.balance(&alice), 580);
+    assert_eq!(token_client.balance(&bob), 570);
+    assert_eq!(token_client.balance(&carol), 560);
+    assert_eq!(token_client.balance(&keeper), 90);
+    assert_eq!(client.get_keeper_fee(&keeper), 90);
+    assert_eq!(token_client.balance(&client.address), 1_000_000 - 1_800);
+}
@@ -0,0 +1,69 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    vec, Address, Env,
+};
+
+use vesting_contracts::{AutoClaimPreferences, VestingContractClient};
+
+mod common;
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, Address, u64) {
+    let (client, _admin, _token_addr) = common::setup(env);
+
+    let beneficiary = Address::generate(env);
+    let vault_id = common::create_vault(env, &client, &beneficiary, 10);
+
+    (client, beneficiary, vault_id)
+}
+
+#[test]
+#[should_panic(expected = "Auto-claim not allowed by beneficiary preferences")]
+fn auto_claim_rejects_opted_out_vault() {
+    let env = Env::default();
+    let (client, _beneficiary, vault_id) = setup(&env);
+
+    client.set_auto_claim_preferences(
+        &vault_id,
+        &AutoClaimPreferences {
+            min_claim_amount: 0,
+            min_interval: 0,
+            opted_out: true,
+        },
+    );
+
+    env.ledger().with_mut(|li| li.timestamp += 500);
+    client.auto_claim(&vault_id, &Address::generate(&env));
+}
+
+#[test]
+fn auto_claim_respects_min_amount_and_interval() {
+    let env = Env::default();
+    let (client, _beneficiary, vault_id) = setup(&env);
+    let keeper = Address::generate(&env);
+
+    client.set_auto_claim_preferences(
+        &vault_id,
+        &AutoClaimPreferences {
+            min_claim_amount: 200,
+            min_interval: 300,
+            opted_out: false,
+        },
+    );
+
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    assert!(!client.is_auto_claim_eligible(&vault_id));
+
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    assert!(client.is_auto_claim_eligible(&vault_id));
+    assert_eq!(client.get_auto_claim_eligible_vaults(&1, &10), vec![&env, vault_id]);
+    client.auto_claim(&vault_id, &keeper);
+
+    // Enough has vested again, but the interval has not elapsed
+    env.ledger().with_mut(|li| li.timestamp += 250);
+    assert!(!client.is_auto_claim_eligible(&vault_id));
+    let results = client.auto_claim_many(&vec![&env, vault_id], &keeper);
+    assert!(!results.get(0).unwrap().claimed);
+
+    env.ledger().with_mut(|li| li.timestamp += 50);
+    assert!(client.is_auto_claim_eligible(&vault_id));
+}
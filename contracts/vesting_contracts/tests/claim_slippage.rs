@@ -0,0 +1,74 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    token, Address, Env,
+};
+
+use vesting_contracts::VestingContractClient;
+
+mod common;
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, Address, u64) {
+    let (client, _admin, token_addr) = common::setup(env);
+
+    let beneficiary = Address::generate(env);
+    let vault_id = common::create_vault(env, &client, &beneficiary, 0);
+
+    (client, token_addr, vault_id)
+}
+
+#[test]
+fn preview_claim_matches_claim_payout() {
+    let env = Env::default();
+    let (client, token_addr, vault_id) = setup(&env);
+
+    // Synced yield is paid on top of principal and included in the preview
+    token::StellarAssetClient::new(&env, &token_addr).mint(&client.address, &100i128);
+    client.sync_yield();
+    env.ledger().with_mut(|li| li.timestamp += 1_000);
+
+    let preview = client.preview_claim(&vault_id, &400i128);
+    assert_eq!(preview, 500);
+    assert_eq!(client.claim_tokens(&vault_id, &400i128, &preview), 500);
+
+    // The claim is recorded once
+    assert_eq!(client.get_vault(&vault_id).released_amount, 400);
+    assert!(client.check_invariant());
+}
+
+#[test]
+fn delegate_claim_returns_tokens_paid_out() {
+    let env = Env::default();
+    let (client, token_addr, vault_id) = setup(&env);
+
+    token::StellarAssetClient::new(&env, &token_addr).mint(&client.address, &100i128);
+    client.sync_yield();
+    client.set_delegate(&vault_id, &Some(Address::generate(&env)));
+    env.ledger().with_mut(|li| li.timestamp += 1_000);
+
+    let owner = client.get_vault(&vault_id).owner;
+    assert_eq!(client.claim_as_delegate(&vault_id, &400i128, &0i128), 500);
+    assert_eq!(token::Client::new(&env, &token_addr).balance(&owner), 500);
+    assert_eq!(client.get_vault(&vault_id).released_amount, 400);
+    assert!(client.check_invariant());
+}
+
+#[test]
+#[should_panic(expected = "Tokens out below minimum")]
+fn claim_tokens_rejects_payout_below_minimum() {
+    let env = Env::default();
+    let (client, _token_addr, vault_id) = setup(&env);
+
+    env.ledger().with_mut(|li| li.timestamp += 1_000);
+    client.claim_tokens(&vault_id, &400i128, &401i128);
+}
+
+#[test]
+#[should_panic(expected = "Tokens out below minimum")]
+fn claim_as_delegate_rejects_payout_below_minimum() {
+    let env = Env::default();
+    let (client, _token_addr, vault_id) = setup(&env);
+
+    client.set_delegate(&vault_id, &Some(Address::generate(&env)));
+    env.ledger().with_mut(|li| li.timestamp += 1_000);
+    client.claim_as_delegate(&vault_id, &400i128, &401i128);
+}
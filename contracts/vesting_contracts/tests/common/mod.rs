@@ -0,0 +1,78 @@
+// Fixtures shared by the integration tests. Every test file compiles its own copy of this
+// module and uses only part of it.
+#![allow(dead_code)]
+
+use soroban_sdk::{testutils::Address as _, token, Address, Env, Vec};
+
+use vesting_contracts::{InitConfig, Milestone, Policy, VestingContract, VestingContractClient};
+
+/// Tokens minted to the contract and declared as its initial supply
+pub const INITIAL_SUPPLY: i128 = 1_000_000;
+
+/// Register and initialize a funded vesting contract; returns (client, admin, token)
+pub fn setup(env: &Env) -> (VestingContractClient<'static>, Address, Address) {
+    setup_with_policy(env, Policy::default())
+}
+
+pub fn setup_with_policy(
+    env: &Env,
+    policy: Policy,
+) -> (VestingContractClient<'static>, Address, Address) {
+    env.mock_all_auths();
+
+    let contract_id = env.register(VestingContract, ());
+    let client = VestingContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    let token_addr = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(env, &token_addr).mint(&contract_id, &INITIAL_SUPPLY);
+    client.initialize(&InitConfig {
+        admin: admin.clone(),
+        token: token_addr.clone(),
+        initial_supply: INITIAL_SUPPLY,
+        whitelist: Vec::new(env),
+        policy,
+    });
+
+    (client, admin, token_addr)
+}
+
+/// Create a revocable 1_000 token vault for `owner` that vests linearly over 1_000 seconds
+pub fn create_vault(
+    env: &Env,
+    client: &VestingContractClient,
+    owner: &Address,
+    keeper_fee: i128,
+) -> u64 {
+    let now = env.ledger().timestamp();
+    client.create_vault_full(
+        owner,
+        &1_000i128,
+        &now,
+        &(now + 1_000),
+        &keeper_fee,
+        &true,
+        &false,
+        &0u64,
+    )
+}
+
+/// A locked milestone releasing `percentage` of the vault at once when unlocked
+pub fn milestone(id: u64, percentage: u32) -> Milestone {
+    Milestone {
+        id,
+        percentage,
+        is_unlocked: false,
+        approver: None,
+        evidence_hash: None,
+        unlocked_by: None,
+        unlocked_at: 0,
+        deadline: 0,
+        is_forfeited: false,
+        vesting_duration: 0,
+        step_duration: 0,
+        challenge_ends_at: 0,
+    }
+}
@@ -0,0 +1,244 @@
+use soroban_sdk::{testutils::Address as _, token, vec, Address, BytesN, Env, String};
+
+use vesting_contracts::{VestingContractClient, VestingFactory, VestingFactoryClient};
+
+// Built by `make build` (stellar contract build) or the CI workflow before the tests run
+const VESTING_WASM: &[u8] =
+    include_bytes!("../../../target/wasm32v1-none/release/vesting_contracts.wasm");
+
+fn setup(env: &Env) -> (VestingFactoryClient<'static>, Address, Address) {
+    env.mock_all_auths();
+    // The unoptimized test wasm exceeds the default budget and mainnet code size limit
+    env.cost_estimate().budget().reset_unlimited();
+    env.cost_estimate().disable_resource_limits();
+
+    let wasm_hash = env.deployer().upload_contract_wasm(VESTING_WASM);
+    let factory_id = env.register(VestingFactory, ());
+    let factory = VestingFactoryClient::new(env, &factory_id);
+    factory.initialize_factory(&Address::generate(env), &wasm_hash);
+
+    let admin = Address::generate(env);
+    let token_addr = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(env, &token_addr).mint(&admin, &10_000i128);
+
+    factory.whitelist_token(&token_addr);
+
+    (factory, admin, token_addr)
+}
+
+#[test]
+fn factory_deploys_initialized_and_funded_child() {
+    let env = Env::default();
+    let (factory, admin, token_addr) = setup(&env);
+    let org = String::from_str(&env, "Acme");
+
+    let expected = factory.get_deployment_address(&org, &admin);
+    let child_id = factory.deploy_new_vault_contract(&org, &admin, &5_000i128, &token_addr, &false);
+    assert_eq!(child_id, expected);
+    assert_eq!(factory.get_deployment_count(), 1);
+    assert_eq!(factory.get_deployed_contracts(&0u32, &10u32), vec![&env, child_id.clone()]);
+
+    let child = VestingContractClient::new(&env, &child_id);
+    assert_eq!(child.get_admin(), admin);
+    assert_eq!(token::Client::new(&env, &token_addr).balance(&child_id), 5_000);
+    assert_eq!(child.reconcile().get(0).unwrap().surplus, 0);
+
+    // The child is usable straight away
+    let now = env.ledger().timestamp();
+    let vault_id = child.create_vault_full(
+        &Address::generate(&env),
+        &1_000i128,
+        &now,
+        &(now + 1_000),
+        &0i128,
+        &true,
+        &false,
+        &0u64,
+    );
+    assert_eq!(child.get_vault(&vault_id).total_amount, 1_000);
+}
+
+#[test]
+fn deployment_address_depends_on_org_and_admin() {
+    let env = Env::default();
+    let (factory, admin, token_addr) = setup(&env);
+
+    let a = factory.deploy_new_vault_contract(
+        &String::from_str(&env, "Acme"),
+        &admin,
+        &0i128,
+        &token_addr,
+        &false,
+    );
+    let b = factory.deploy_new_vault_contract(
+        &String::from_str(&env, "Globex"),
+        &admin,
+        &0i128,
+        &token_addr,
+        &false,
+    );
+    assert_ne!(a, b);
+    assert_eq!(factory.get_deployment_count(), 2);
+    assert_eq!(factory.get_deployed_contracts(&0u32, &10u32), vec![&env, a, b]);
+}
+
+#[test]
+#[should_panic(expected = "Token not whitelisted")]
+fn factory_rejects_non_whitelisted_token() {
+    let env = Env::default();
+    let (factory, admin, _token_addr) = setup(&env);
+    let other = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+
+    factory.deploy_new_vault_contract(
+        &String::from_str(&env, "Acme"),
+        &admin,
+        &0i128,
+        &other,
+        &false,
+    );
+}
+
+#[test]
+fn factory_owner_manages_whitelist_and_wasm_history() {
+    let env = Env::default();
+    let (factory, _admin, token_addr) = setup(&env);
+    let original = factory.get_wasm_hash().unwrap();
+
+    let next = BytesN::from_array(&env, &[1u8; 32]);
+    factory.update_wasm_hash(&next);
+    assert_eq!(factory.get_wasm_hash(), Some(next.clone()));
+    assert_eq!(factory.get_wasm_hash_history(), vec![&env, original, next]);
+
+    assert_eq!(factory.get_factory_whitelist(), vec![&env, token_addr.clone()]);
+    factory.unwhitelist_token(&token_addr);
+    assert_eq!(factory.get_factory_whitelist().len(), 0);
+}
+
+#[test]
+#[should_panic]
+fn update_wasm_hash_requires_owner() {
+    let env = Env::default();
+    let factory = VestingFactoryClient::new(&env, &env.register(VestingFactory, ()));
+    let hash = BytesN::from_array(&env, &[0u8; 32]);
+
+    env.mock_all_auths();
+    factory.initialize_factory(&Address::generate(&env), &hash);
+
+    env.set_auths(&[]);
+    factory.update_wasm_hash(&BytesN::from_array(&env, &[1u8; 32]));
+}
+
+#[test]
+#[should_panic(expected = "Factory already initialized")]
+fn initialize_factory_is_one_shot() {
+    let env = Env::default();
+    let (factory, _admin, _token_addr) = setup(&env);
+    factory.initialize_factory(&Address::generate(&env), &BytesN::from_array(&env, &[1u8; 32]));
+}
+
+#[test]
+fn registry_finds_deployments_by_org_admin_and_token() {
+    let env = Env::default();
+    let (factory, admin, token_addr) = setup(&env);
+    let acme = String::from_str(&env, "Acme");
+    let globex = String::from_str(&env, "Globex");
+
+    let a = factory.deploy_new_vault_contract(&acme, &admin, &0i128, &token_addr, &false);
+    let b = factory.deploy_new_vault_contract(&globex, &admin, &0i128, &token_addr, &false);
+
+    let record = factory.get_deployment_by_org(&acme, &admin).unwrap();
+    assert_eq!(record.contract, a);
+    assert_eq!(record.org_name, acme);
+    assert_eq!(record.token, token_addr);
+    assert_eq!(record.wasm_version, 1);
+    assert_eq!(record.created_at, env.ledger().timestamp());
+    assert_eq!(factory.get_deployment_by_org(&acme, &Address::generate(&env)), None);
+
+    assert_eq!(factory.get_deployments_by_admin(&admin, &0u32, &10u32).len(), 2);
+    assert_eq!(factory.get_deployments_by_token(&token_addr, &0u32, &10u32).len(), 2);
+    let stranger = Address::generate(&env);
+    assert_eq!(factory.get_deployments_by_admin(&stranger, &0u32, &10u32).len(), 0);
+
+    let page = factory.get_deployments(&1u32, &10u32);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().contract, b);
+}
+
+#[test]
+fn registry_pages_are_bounded_by_start_and_limit() {
+    let env = Env::default();
+    let (factory, admin, token_addr) = setup(&env);
+    let other_admin = Address::generate(&env);
+
+    let mut deployed = vec![&env];
+    for name in ["Acme", "Globex", "Initech"] {
+        let org = String::from_str(&env, name);
+        deployed.push_back(factory.deploy_new_vault_contract(&org, &admin, &0i128, &token_addr, &false));
+    }
+    let umbrella = String::from_str(&env, "Umbrella");
+    let other = factory.deploy_new_vault_contract(&umbrella, &other_admin, &0i128, &token_addr, &false);
+
+    assert_eq!(factory.get_deployment_count(), 4);
+    assert_eq!(
+        factory.get_deployed_contracts(&1u32, &2u32),
+        vec![&env, deployed.get(1).unwrap(), deployed.get(2).unwrap()]
+    );
+    assert_eq!(factory.get_deployed_contracts(&4u32, &2u32).len(), 0);
+
+    let by_admin = factory.get_deployments_by_admin(&admin, &2u32, &5u32);
+    assert_eq!(by_admin.len(), 1);
+    assert_eq!(by_admin.get(0).unwrap().contract, deployed.get(2).unwrap());
+
+    let by_token = factory.get_deployments_by_token(&token_addr, &3u32, &1u32);
+    assert_eq!(by_token.len(), 1);
+    assert_eq!(by_token.get(0).unwrap().contract, other);
+}
+
+#[test]
+fn rollout_upgrades_only_opted_in_children() {
+    let env = Env::default();
+    let (factory, admin, token_addr) = setup(&env);
+    let acme = String::from_str(&env, "Acme");
+    let globex = String::from_str(&env, "Globex");
+
+    let opted_in = factory.deploy_new_vault_contract(&acme, &admin, &0i128, &token_addr, &true);
+    let opted_out = factory.deploy_new_vault_contract(&globex, &admin, &0i128, &token_addr, &false);
+    assert_eq!(
+        VestingContractClient::new(&env, &opted_in).get_upgrade_authority(),
+        Some(factory.address.clone())
+    );
+
+    // Re-publish the template so the rollout targets version 2
+    let wasm_hash = factory.get_wasm_hash().unwrap();
+    factory.update_wasm_hash(&wasm_hash);
+
+    let children = vec![&env, opted_in.clone(), opted_out.clone()];
+    let upgraded = factory.rollout_upgrade(&children, &wasm_hash);
+    assert_eq!(upgraded, vec![&env, opted_in.clone()]);
+    assert_eq!(factory.get_deployment(&opted_in).unwrap().wasm_version, 2);
+    assert_eq!(factory.get_deployment(&opted_out).unwrap().wasm_version, 1);
+}
+
+#[test]
+fn upgrade_rejects_unauthorized_caller() {
+    let env = Env::default();
+    let (factory, admin, token_addr) = setup(&env);
+    let child_id = factory.deploy_new_vault_contract(
+        &String::from_str(&env, "Acme"),
+        &admin,
+        &0i128,
+        &token_addr,
+        &true,
+    );
+
+    // Children run as wasm, which drops panic messages, so only the failure is visible
+    let child = VestingContractClient::new(&env, &child_id);
+    let wasm_hash = factory.get_wasm_hash().unwrap();
+    assert!(child
+        .try_upgrade(&Address::generate(&env), &wasm_hash)
+        .is_err());
+}
@@ -0,0 +1,187 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _, MockAuth, MockAuthInvoke},
+    token, Address, Env, IntoVal, Vec,
+};
+
+use vesting_contracts::{InitConfig, Policy, VestingContract, VestingContractClient};
+
+struct Setup {
+    client: VestingContractClient<'static>,
+    contract_id: Address,
+    token: token::Client<'static>,
+    minter: token::StellarAssetClient<'static>,
+}
+
+fn setup(env: &Env) -> Setup {
+    env.mock_all_auths();
+
+    let contract_id = env.register(VestingContract, ());
+    let client = VestingContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    let token_addr = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    client.initialize(&InitConfig {
+        admin: admin.clone(),
+        token: token_addr.clone(),
+        initial_supply: 0,
+        whitelist: Vec::new(env),
+        policy: Policy::default(),
+    });
+
+    Setup {
+        client,
+        contract_id,
+        token: token::Client::new(env, &token_addr),
+        minter: token::StellarAssetClient::new(env, &token_addr),
+    }
+}
+
+#[test]
+fn fund_pulls_tokens_and_credits_admin_balance() {
+    let env = Env::default();
+    let s = setup(&env);
+    let treasury = Address::generate(&env);
+    s.minter.mint(&treasury, &5_000i128);
+
+    s.client.fund(&treasury, &5_000i128);
+
+    assert_eq!(s.token.balance(&treasury), 0);
+    assert_eq!(s.token.balance(&s.contract_id), 5_000);
+    assert_eq!(s.client.get_contract_state().2, 5_000);
+    assert!(s.client.check_invariant());
+
+    s.client.withdraw_admin_balance(&treasury, &2_000i128);
+    assert_eq!(s.token.balance(&treasury), 2_000);
+    assert_eq!(s.client.get_contract_state().2, 3_000);
+    assert!(s.client.check_invariant());
+}
+
+#[test]
+fn sponsor_funds_a_specific_grant() {
+    let env = Env::default();
+    let s = setup(&env);
+    let sponsor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    s.minter.mint(&sponsor, &1_000i128);
+
+    let now = env.ledger().timestamp();
+    let vault_id = s.client.create_vault_from_funder(
+        &sponsor,
+        &beneficiary,
+        &1_000i128,
+        &now,
+        &(now + 1_000),
+        &0i128,
+        &true,
+        &false,
+        &0u64,
+    );
+
+    assert_eq!(s.client.get_vault(&vault_id).total_amount, 1_000);
+    assert_eq!(s.token.balance(&sponsor), 0);
+    assert_eq!(s.token.balance(&s.contract_id), 1_000);
+    // The sponsor's tokens are fully allocated to the grant
+    assert_eq!(s.client.get_contract_state().2, 0);
+    assert_eq!(s.client.get_vault_funder(&vault_id), Some(sponsor));
+}
+
+#[test]
+fn revoked_sponsor_tokens_return_to_sponsor() {
+    let env = Env::default();
+    let s = setup(&env);
+    let sponsor = Address::generate(&env);
+    s.minter.mint(&sponsor, &1_000i128);
+
+    let now = env.ledger().timestamp();
+    let vault_id = s.client.create_vault_from_funder(
+        &sponsor,
+        &Address::generate(&env),
+        &1_000i128,
+        &now,
+        &(now + 1_000),
+        &0i128,
+        &true,
+        &false,
+        &0u64,
+    );
+
+    env.ledger().with_mut(|l| l.timestamp = now + 400);
+    s.client.revoke_partial(&vault_id, &200i128);
+    assert_eq!(s.token.balance(&sponsor), 200);
+
+    s.client.revoke_tokens(&vault_id);
+    assert_eq!(s.token.balance(&sponsor), 1_000);
+    assert_eq!(s.token.balance(&s.contract_id), 0);
+    // Nothing was credited to the admin, who cannot withdraw the sponsor's tokens
+    assert_eq!(s.client.get_contract_state().2, 0);
+    assert!(s.client.check_invariant());
+}
+
+#[test]
+fn create_vault_from_funder_requires_admin_auth() {
+    let env = Env::default();
+    let s = setup(&env);
+    let sponsor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    s.minter.mint(&sponsor, &1_000i128);
+
+    let now = env.ledger().timestamp();
+    let args = (
+        sponsor.clone(),
+        beneficiary.clone(),
+        1_000i128,
+        now,
+        now + 1_000,
+        0i128,
+        true,
+        false,
+        0u64,
+    );
+    // Only the sponsor signs, for both the call and the token transfer it makes
+    env.mock_auths(&[MockAuth {
+        address: &sponsor,
+        invoke: &MockAuthInvoke {
+            contract: &s.contract_id,
+            fn_name: "create_vault_from_funder",
+            args: args.clone().into_val(&env),
+            sub_invokes: &[MockAuthInvoke {
+                contract: &s.token.address,
+                fn_name: "transfer",
+                args: (sponsor.clone(), s.contract_id.clone(), 1_000i128).into_val(&env),
+                sub_invokes: &[],
+            }],
+        },
+    }]);
+
+    let result = s.client.try_create_vault_from_funder(
+        &args.0, &args.1, &args.2, &args.3, &args.4, &args.5, &args.6, &args.7, &args.8,
+    );
+    assert!(result.is_err());
+    assert_eq!(s.token.balance(&sponsor), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient admin balance")]
+fn withdraw_admin_balance_cannot_touch_allocated_tokens() {
+    let env = Env::default();
+    let s = setup(&env);
+    let treasury = Address::generate(&env);
+    s.minter.mint(&treasury, &1_000i128);
+    s.client.fund(&treasury, &1_000i128);
+
+    let now = env.ledger().timestamp();
+    s.client.create_vault_full(
+        &Address::generate(&env),
+        &600i128,
+        &now,
+        &(now + 1_000),
+        &0i128,
+        &true,
+        &false,
+        &0u64,
+    );
+
+    s.client.withdraw_admin_balance(&treasury, &500i128);
+}
@@ -0,0 +1,104 @@
+use soroban_sdk::{testutils::Address as _, token, vec, Address, Env, Vec};
+
+use vesting_contracts::{InitConfig, Policy, VestingContract, VestingContractClient};
+
+// Config for `client` with its initial supply already minted to the contract
+fn config(env: &Env, client: &VestingContractClient, admin: &Address) -> InitConfig {
+    let token_addr = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(env, &token_addr).mint(&client.address, &1_000_000i128);
+    InitConfig {
+        admin: admin.clone(),
+        token: token_addr,
+        initial_supply: 1_000_000,
+        whitelist: Vec::new(env),
+        policy: Policy::default(),
+    }
+}
+
+#[test]
+fn initialize_applies_full_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = VestingContractClient::new(&env, &env.register(VestingContract, ()));
+
+    let admin = Address::generate(&env);
+    let extra = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let mut cfg = config(&env, &client, &admin);
+    cfg.whitelist = vec![&env, extra.clone()];
+    cfg.policy.max_duration = 1_000;
+    client.initialize(&cfg);
+
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_contract_state().2, 1_000_000);
+
+    let reports = client.reconcile();
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports.get(0).unwrap().token, cfg.token);
+    assert_eq!(reports.get(1).unwrap().token, extra);
+}
+
+#[test]
+#[should_panic(expected = "duration exceeds max_duration")]
+fn initialize_max_duration_is_enforced() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = VestingContractClient::new(&env, &env.register(VestingContract, ()));
+
+    let admin = Address::generate(&env);
+    let mut cfg = config(&env, &client, &admin);
+    cfg.policy.max_duration = 1_000;
+    client.initialize(&cfg);
+
+    let now = env.ledger().timestamp();
+    client.create_vault_full(
+        &Address::generate(&env),
+        &1_000i128,
+        &now,
+        &(now + 1_001),
+        &0i128,
+        &true,
+        &false,
+        &0u64,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Already initialized")]
+fn initialize_cannot_be_called_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = VestingContractClient::new(&env, &env.register(VestingContract, ()));
+
+    client.initialize(&config(&env, &client, &Address::generate(&env)));
+    // A second caller must not be able to take over the admin role
+    client.initialize(&config(&env, &client, &Address::generate(&env)));
+}
+
+#[test]
+#[should_panic(expected = "Initial supply not held by contract")]
+fn initialize_rejects_unbacked_initial_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = VestingContractClient::new(&env, &env.register(VestingContract, ()));
+
+    let mut cfg = config(&env, &client, &Address::generate(&env));
+    cfg.initial_supply = 1_000_001;
+    client.initialize(&cfg);
+}
+
+#[test]
+fn initialize_requires_admin_auth() {
+    let env = Env::default();
+    let client = VestingContractClient::new(&env, &env.register(VestingContract, ()));
+
+    env.mock_all_auths();
+    let cfg = config(&env, &client, &Address::generate(&env));
+    env.set_auths(&[]);
+
+    assert!(client.try_initialize(&cfg).is_err());
+    assert!(client.try_get_admin().is_err());
+}
@@ -1,15 +1,11 @@
 use soroban_sdk::{testutils::Address as _, vec, Address, Env};
 
-use vesting_contracts::{BatchCreateData, VestingContract, VestingContractClient, MAX_DURATION};
+use vesting_contracts::{BatchCreateData, VestingContractClient, MAX_DURATION};
 
-fn setup(env: &Env) -> (VestingContractClient<'static>, Address) {
-    env.mock_all_auths();
-
-    let contract_id = env.register(VestingContract, ());
-    let client = VestingContractClient::new(env, &contract_id);
+mod common;
 
-    let admin = Address::generate(env);
-    client.initialize(&admin, &1_000_000i128);
+fn setup(env: &Env) -> (VestingContractClient<'static>, Address) {
+    let (client, admin, _token_addr) = common::setup(env);
 
     (client, admin)
 }
@@ -36,7 +32,7 @@ fn create_vault_full_allows_max_duration() {
 }
 
 #[test]
-#[should_panic(expected = "duration exceeds MAX_DURATION")]
+#[should_panic(expected = "duration exceeds max_duration")]
 fn create_vault_full_rejects_over_max_duration() {
     let env = Env::default();
     let (client, _admin) = setup(&env);
@@ -58,7 +54,7 @@ fn create_vault_full_rejects_over_max_duration() {
 }
 
 #[test]
-#[should_panic(expected = "duration exceeds MAX_DURATION")]
+#[should_panic(expected = "duration exceeds max_duration")]
 fn create_vault_lazy_rejects_over_max_duration() {
     let env = Env::default();
     let (client, _admin) = setup(&env);
@@ -80,7 +76,7 @@ fn create_vault_lazy_rejects_over_max_duration() {
 }
 
 #[test]
-#[should_panic(expected = "duration exceeds MAX_DURATION")]
+#[should_panic(expected = "duration exceeds max_duration")]
 fn batch_create_vaults_rejects_over_max_duration() {
     let env = Env::default();
     let (client, _admin) = setup(&env);
@@ -0,0 +1,61 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Env};
+
+use vesting_contracts::{Milestone, VestingContractClient};
+
+mod common;
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, Address, u64) {
+    let (client, admin, _token_addr) = common::setup(env);
+
+    let beneficiary = Address::generate(env);
+    let vault_id = common::create_vault(env, &client, &beneficiary, 0);
+
+    (client, admin, vault_id)
+}
+
+fn milestone(id: u64, percentage: u32, approver: Option<Address>) -> Milestone {
+    Milestone {
+        approver,
+        ..common::milestone(id, percentage)
+    }
+}
+
+#[test]
+fn unlock_milestone_records_approver_and_evidence() {
+    let env = Env::default();
+    let (client, admin, vault_id) = setup(&env);
+    let oracle = Address::generate(&env);
+
+    client.set_milestones(
+        &vault_id,
+        &vec![
+            &env,
+            milestone(1, 50, Some(oracle.clone())),
+            milestone(2, 50, None),
+        ],
+    );
+
+    let evidence = BytesN::from_array(&env, &[7u8; 32]);
+    client.unlock_milestone(&vault_id, &1u64, &evidence);
+    client.unlock_milestone(&vault_id, &2u64, &BytesN::from_array(&env, &[9u8; 32]));
+
+    let milestones = client.get_milestones(&vault_id);
+    let first = milestones.get(0).unwrap();
+    assert!(first.is_unlocked);
+    assert_eq!(first.unlocked_by, Some(oracle));
+    assert_eq!(first.evidence_hash, Some(evidence));
+    assert_eq!(first.unlocked_at, env.ledger().timestamp());
+
+    assert_eq!(milestones.get(1).unwrap().unlocked_by, Some(admin));
+}
+
+#[test]
+#[should_panic(expected = "Milestones must be created locked")]
+fn set_milestones_rejects_prefilled_unlock() {
+    let env = Env::default();
+    let (client, _admin, vault_id) = setup(&env);
+
+    let mut m = milestone(1, 100, None);
+    m.is_unlocked = true;
+    client.set_milestones(&vault_id, &vec![&env, m]);
+}
@@ -0,0 +1,71 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    vec, Address, BytesN, Env,
+};
+
+use vesting_contracts::{Milestone, VestingContractClient};
+
+mod common;
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, u64) {
+    let (client, _admin, _token_addr) = common::setup(env);
+
+    let beneficiary = Address::generate(env);
+    let vault_id = common::create_vault(env, &client, &beneficiary, 0);
+
+    (client, vault_id)
+}
+
+fn milestone(id: u64, percentage: u32, deadline: u64) -> Milestone {
+    Milestone {
+        deadline,
+        ..common::milestone(id, percentage)
+    }
+}
+
+#[test]
+fn expire_milestone_returns_share_to_admin() {
+    let env = Env::default();
+    let (client, vault_id) = setup(&env);
+    let now = env.ledger().timestamp();
+
+    client.set_milestones(
+        &vault_id,
+        &vec![&env, milestone(1, 40, now + 100), milestone(2, 60, 0)],
+    );
+
+    let (_, _, admin_before) = client.get_contract_state();
+    env.ledger().with_mut(|li| li.timestamp = now + 101);
+
+    let forfeited = client.expire_milestone(&vault_id, &1u64);
+    assert_eq!(forfeited, 400);
+
+    let (locked, _, admin_after) = client.get_contract_state();
+    assert_eq!(admin_after, admin_before + 400);
+    assert_eq!(locked, 600);
+    assert!(client.get_milestones(&vault_id).get(0).unwrap().is_forfeited);
+    assert!(client.check_invariant());
+}
+
+#[test]
+#[should_panic(expected = "Milestone deadline not reached")]
+fn expire_milestone_before_deadline_panics() {
+    let env = Env::default();
+    let (client, vault_id) = setup(&env);
+    let now = env.ledger().timestamp();
+
+    client.set_milestones(&vault_id, &vec![&env, milestone(1, 100, now + 100)]);
+    client.expire_milestone(&vault_id, &1u64);
+}
+
+#[test]
+#[should_panic(expected = "Milestone deadline passed")]
+fn unlock_after_deadline_panics() {
+    let env = Env::default();
+    let (client, vault_id) = setup(&env);
+    let now = env.ledger().timestamp();
+
+    client.set_milestones(&vault_id, &vec![&env, milestone(1, 100, now + 100)]);
+    env.ledger().with_mut(|li| li.timestamp = now + 101);
+    client.unlock_milestone(&vault_id, &1u64, &BytesN::from_array(&env, &[1u8; 32]));
+}
@@ -0,0 +1,71 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    vec, Address, BytesN, Env,
+};
+
+use vesting_contracts::VestingContractClient;
+
+mod common;
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, Address, u64) {
+    let (client, admin, _token_addr) = common::setup(env);
+
+    let beneficiary = Address::generate(env);
+    let vault_id = common::create_vault(env, &client, &beneficiary, 0);
+
+    client.set_milestones(&vault_id, &vec![env, common::milestone(1, 100)]);
+    client.set_milestone_challenge_window(&100u64);
+
+    (client, admin, vault_id)
+}
+
+#[test]
+#[should_panic(expected = "No tokens available to claim")]
+fn unlocked_milestone_not_claimable_during_challenge_window() {
+    let env = Env::default();
+    let (client, _admin, vault_id) = setup(&env);
+
+    client.unlock_milestone(&vault_id, &1u64, &BytesN::from_array(&env, &[1u8; 32]));
+    env.ledger().with_mut(|li| li.timestamp += 99);
+    client.claim_tokens(&vault_id, &1i128, &0i128);
+}
+
+#[test]
+fn arbiter_can_relock_and_milestone_can_be_unlocked_again() {
+    let env = Env::default();
+    let (client, _admin, vault_id) = setup(&env);
+    let arbiter = Address::generate(&env);
+    client.set_milestone_arbiter(&Some(arbiter.clone()));
+
+    client.unlock_milestone(&vault_id, &1u64, &BytesN::from_array(&env, &[1u8; 32]));
+    client.relock_milestone(&arbiter, &vault_id, &1u64, &3u32);
+
+    let m = client.get_milestones(&vault_id).get(0).unwrap();
+    assert!(!m.is_unlocked);
+    assert_eq!(m.evidence_hash, None);
+
+    client.unlock_milestone(&vault_id, &1u64, &BytesN::from_array(&env, &[2u8; 32]));
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    assert_eq!(client.claim_tokens(&vault_id, &1_000i128, &0i128), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Milestone unlock is final")]
+fn relock_after_window_panics() {
+    let env = Env::default();
+    let (client, admin, vault_id) = setup(&env);
+
+    client.unlock_milestone(&vault_id, &1u64, &BytesN::from_array(&env, &[1u8; 32]));
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    client.relock_milestone(&admin, &vault_id, &1u64, &1u32);
+}
+
+#[test]
+#[should_panic(expected = "Caller is not admin or arbiter")]
+fn relock_by_stranger_panics() {
+    let env = Env::default();
+    let (client, _admin, vault_id) = setup(&env);
+
+    client.unlock_milestone(&vault_id, &1u64, &BytesN::from_array(&env, &[1u8; 32]));
+    client.relock_milestone(&Address::generate(&env), &vault_id, &1u64, &1u32);
+}
@@ -0,0 +1,90 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    token, vec, Address, BytesN, Env,
+};
+
+use vesting_contracts::{Milestone, VestingContractClient};
+
+mod common;
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, Address, u64) {
+    let (client, _admin, token_addr) = common::setup(env);
+
+    let beneficiary = Address::generate(env);
+    let vault_id = common::create_vault(env, &client, &beneficiary, 0);
+
+    (client, token_addr, vault_id)
+}
+
+fn milestone(id: u64, percentage: u32, vesting_duration: u64, step_duration: u64) -> Milestone {
+    Milestone {
+        vesting_duration,
+        step_duration,
+        ..common::milestone(id, percentage)
+    }
+}
+
+#[test]
+fn unlocked_tranche_vests_linearly_after_unlock() {
+    let env = Env::default();
+    let (client, _token, vault_id) = setup(&env);
+
+    client.set_milestones(
+        &vault_id,
+        &vec![&env, milestone(1, 50, 1_000, 0), milestone(2, 50, 0, 0)],
+    );
+
+    client.unlock_milestone(&vault_id, &1u64, &BytesN::from_array(&env, &[1u8; 32]));
+    env.ledger().with_mut(|li| li.timestamp += 400);
+
+    // 40% of the 500 tranche
+    assert_eq!(client.claim_tokens(&vault_id, &200i128, &0i128), 200);
+
+    // Immediate tranche is added on top of the vesting one
+    client.unlock_milestone(&vault_id, &2u64, &BytesN::from_array(&env, &[2u8; 32]));
+    env.ledger().with_mut(|li| li.timestamp += 600);
+    assert_eq!(client.claim_tokens(&vault_id, &800i128, &0i128), 800);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient unlocked tokens to claim")]
+fn stepped_tranche_rounds_down_to_completed_steps() {
+    let env = Env::default();
+    let (client, _token, vault_id) = setup(&env);
+
+    client.set_milestones(&vault_id, &vec![&env, milestone(1, 100, 1_000, 250)]);
+    client.unlock_milestone(&vault_id, &1u64, &BytesN::from_array(&env, &[1u8; 32]));
+
+    // One step (250s) completed: 250 claimable, 251 is not
+    env.ledger().with_mut(|li| li.timestamp += 499);
+    client.claim_tokens(&vault_id, &251i128, &0i128);
+}
+
+#[test]
+fn auto_claim_follows_milestones_not_the_time_schedule() {
+    let env = Env::default();
+    let (client, token_addr, vault_id) = setup(&env);
+    let keeper = Address::generate(&env);
+
+    client.set_milestones(
+        &vault_id,
+        &vec![&env, milestone(1, 50, 1_000, 0), milestone(2, 50, 0, 0)],
+    );
+
+    // The vault's time schedule has fully elapsed, but no milestone is unlocked
+    env.ledger().with_mut(|li| li.timestamp += 1_000);
+    assert_eq!(client.get_claimable_amount(&vault_id), 0);
+    assert!(client.try_auto_claim(&vault_id, &keeper).is_err());
+    let results = client.auto_claim_many(&vec![&env, vault_id], &keeper);
+    assert!(!results.get(0).unwrap().claimed);
+
+    // Only the vested part of the unlocked tranche can be auto-claimed
+    client.unlock_milestone(&vault_id, &1u64, &BytesN::from_array(&env, &[1u8; 32]));
+    env.ledger().with_mut(|li| li.timestamp += 400);
+    assert_eq!(client.get_claimable_amount(&vault_id), 200);
+
+    client.auto_claim(&vault_id, &keeper);
+    let owner = client.get_vault(&vault_id).owner;
+    assert_eq!(token::Client::new(&env, &token_addr).balance(&owner), 200);
+    assert_eq!(client.get_vault(&vault_id).released_amount, 200);
+}
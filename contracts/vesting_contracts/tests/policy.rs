@@ -0,0 +1,204 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    vec, Address, Env,
+};
+
+use vesting_contracts::{BatchCreateData, Policy, VestingContractClient, POLICY_UPDATE_DELAY};
+
+mod common;
+
+fn setup(env: &Env, policy: Policy) -> VestingContractClient<'static> {
+    let (client, _admin, _token_addr) = common::setup_with_policy(env, policy);
+
+    client
+}
+
+fn create_vault(
+    env: &Env,
+    client: &VestingContractClient,
+    amount: i128,
+    duration: u64,
+    keeper_fee: i128,
+    step_duration: u64,
+) -> u64 {
+    let now = env.ledger().timestamp();
+    client.create_vault_full(
+        &Address::generate(env),
+        &amount,
+        &now,
+        &(now + duration),
+        &keeper_fee,
+        &true,
+        &false,
+        &step_duration,
+    )
+}
+
+#[test]
+fn policy_update_waits_for_delay() {
+    let env = Env::default();
+    let client = setup(&env, Policy::default());
+
+    let policy = Policy {
+        min_vault_amount: 100,
+        ..Policy::default()
+    };
+    client.propose_policy(&policy);
+    assert_eq!(client.get_policy(), Policy::default());
+    assert!(client.try_apply_policy().is_err());
+
+    env.ledger().with_mut(|li| li.timestamp += POLICY_UPDATE_DELAY);
+    client.apply_policy();
+    assert_eq!(client.get_policy(), policy);
+    assert_eq!(client.get_pending_policy(), None);
+}
+
+#[test]
+fn creation_paths_enforce_policy_limits() {
+    let env = Env::default();
+    let client = setup(
+        &env,
+        Policy {
+            min_duration: 100,
+            max_duration: 10_000,
+            max_keeper_fee: 50,
+            max_step_count: 10,
+            clawback_grace_period: 3600,
+            max_batch_size: 1,
+            min_vault_amount: 100,
+            max_vault_amount: 10_000,
+        },
+    );
+
+    create_vault(&env, &client, 1_000, 1_000, 50, 100);
+
+    assert!(client
+        .try_create_vault_full(
+            &Address::generate(&env),
+            &99i128,
+            &0u64,
+            &1_000u64,
+            &0i128,
+            &true,
+            &false,
+            &0u64,
+        )
+        .is_err());
+    assert!(client
+        .try_create_vault_lazy(
+            &Address::generate(&env),
+            &1_000i128,
+            &0u64,
+            &99u64,
+            &0i128,
+            &true,
+            &false,
+            &0u64,
+        )
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "duration exceeds max_duration")]
+fn create_vault_rejects_duration_over_policy_limit() {
+    let env = Env::default();
+    let policy = Policy {
+        max_duration: 10_000,
+        ..Policy::default()
+    };
+    let client = setup(&env, policy);
+
+    create_vault(&env, &client, 1_000, 10_001, 0, 0);
+}
+
+#[test]
+#[should_panic(expected = "keeper_fee exceeds max_keeper_fee")]
+fn create_vault_rejects_keeper_fee_over_limit() {
+    let env = Env::default();
+    let policy = Policy {
+        max_keeper_fee: 50,
+        ..Policy::default()
+    };
+    let client = setup(&env, policy);
+
+    create_vault(&env, &client, 1_000, 1_000, 51, 0);
+}
+
+#[test]
+#[should_panic(expected = "step count exceeds max_step_count")]
+fn create_vault_rejects_too_many_steps() {
+    let env = Env::default();
+    let policy = Policy {
+        max_step_count: 10,
+        ..Policy::default()
+    };
+    let client = setup(&env, policy);
+
+    create_vault(&env, &client, 1_000, 1_000, 0, 99);
+}
+
+#[test]
+#[should_panic(expected = "batch exceeds max_batch_size")]
+fn batch_create_rejects_oversized_batch() {
+    let env = Env::default();
+    let policy = Policy {
+        max_batch_size: 1,
+        ..Policy::default()
+    };
+    let client = setup(&env, policy);
+
+    let now = env.ledger().timestamp();
+    client.batch_create_vaults_full(&BatchCreateData {
+        recipients: vec![&env, Address::generate(&env), Address::generate(&env)],
+        amounts: vec![&env, 100i128, 100i128],
+        start_times: vec![&env, now, now],
+        end_times: vec![&env, now + 1_000, now + 1_000],
+        keeper_fees: vec![&env, 0i128, 0i128],
+        step_durations: vec![&env, 0u64, 0u64],
+    });
+}
+
+#[test]
+#[should_panic(expected = "batch exceeds max_batch_size")]
+fn auto_claim_many_rejects_oversized_batch() {
+    let env = Env::default();
+    let policy = Policy {
+        max_batch_size: 1,
+        ..Policy::default()
+    };
+    let client = setup(&env, policy);
+
+    let a = create_vault(&env, &client, 1_000, 1_000, 0, 0);
+    let b = create_vault(&env, &client, 1_000, 1_000, 0, 0);
+    client.auto_claim_many(&vec![&env, a, b], &Address::generate(&env));
+}
+
+#[test]
+#[should_panic(expected = "batch exceeds max_batch_size")]
+fn batch_revoke_rejects_oversized_batch() {
+    let env = Env::default();
+    let policy = Policy {
+        max_batch_size: 1,
+        ..Policy::default()
+    };
+    let client = setup(&env, policy);
+
+    let a = create_vault(&env, &client, 1_000, 1_000, 0, 0);
+    let b = create_vault(&env, &client, 1_000, 1_000, 0, 0);
+    client.batch_revoke(&vec![&env, a, b]);
+}
+
+#[test]
+#[should_panic(expected = "Grace period expired")]
+fn clawback_uses_policy_grace_period() {
+    let env = Env::default();
+    let policy = Policy {
+        clawback_grace_period: 60,
+        ..Policy::default()
+    };
+    let client = setup(&env, policy);
+
+    let vault_id = create_vault(&env, &client, 1_000, 1_000, 0, 0);
+    env.ledger().with_mut(|li| li.timestamp += 61);
+    client.clawback_vault(&vault_id);
+}
@@ -0,0 +1,109 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    token, Address, Env,
+};
+
+use vesting_contracts::VestingContractClient;
+
+mod common;
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, token::StellarAssetClient<'static>) {
+    let (client, _admin, token_addr) = common::setup(env);
+    let minter = token::StellarAssetClient::new(env, &token_addr);
+
+    (client, minter)
+}
+
+// Simulate tokens leaving the contract without going through it, e.g. an issuer clawback
+fn lose_tokens(env: &Env, client: &VestingContractClient, minter: &token::StellarAssetClient) {
+    token::Client::new(env, &minter.address).burn(&client.address, &600_000i128);
+}
+
+#[test]
+fn reconcile_reports_deficit_against_virtual_supply() {
+    let env = Env::default();
+    let (client, minter) = setup(&env);
+    lose_tokens(&env, &client, &minter);
+
+    let reports = client.reconcile();
+    assert_eq!(reports.len(), 1);
+    let report = reports.get(0).unwrap();
+    assert_eq!(report.balance, 400_000);
+    assert_eq!(report.admin_balance, 1_000_000);
+    assert_eq!(report.surplus, -600_000);
+}
+
+fn create_vault(env: &Env, client: &VestingContractClient, keeper_fee: i128) -> u64 {
+    let now = env.ledger().timestamp();
+    client.create_vault_full(
+        &Address::generate(env),
+        &1_000i128,
+        &now,
+        &(now + 1_000),
+        &keeper_fee,
+        &true,
+        &false,
+        &0u64,
+    )
+}
+
+#[test]
+fn sync_admin_balance_credits_verified_surplus() {
+    let env = Env::default();
+    let (client, minter) = setup(&env);
+    minter.mint(&client.address, &500i128);
+
+    let report = client.reconcile().get(0).unwrap();
+    assert_eq!(report.admin_balance, 1_000_000);
+    assert_eq!(report.surplus, 500);
+
+    // No vault holds shares, so nothing is owed as yield
+    assert_eq!(client.sync_admin_balance(), 500);
+    assert_eq!(client.reconcile().get(0).unwrap().surplus, 0);
+    assert_eq!(client.get_contract_state().2, 1_000_500);
+    assert!(client.check_invariant());
+}
+
+#[test]
+fn sync_admin_balance_leaves_yield_to_vault_shares() {
+    let env = Env::default();
+    let (client, minter) = setup(&env);
+    let vault_id = create_vault(&env, &client, 0);
+    minter.mint(&client.address, &500i128);
+
+    let report = client.reconcile().get(0).unwrap();
+    assert_eq!(report.vault_liabilities, 1_000);
+    assert_eq!(report.admin_balance, 999_000);
+    assert_eq!(report.surplus, 500);
+
+    // The surplus is yield for the vault, not admin funds
+    assert!(client.try_sync_admin_balance().is_err());
+    assert_eq!(client.get_contract_state().2, 999_000);
+    assert_eq!(client.sync_yield(), 500);
+    assert_eq!(client.get_accrued_yield(&vault_id), 500);
+    assert_eq!(client.reconcile().get(0).unwrap().surplus, 0);
+}
+
+#[test]
+fn reconcile_reports_keeper_fees() {
+    let env = Env::default();
+    let (client, _minter) = setup(&env);
+    let vault_id = create_vault(&env, &client, 10);
+
+    env.ledger().with_mut(|li| li.timestamp += 1_000);
+    client.auto_claim(&vault_id, &Address::generate(&env));
+
+    let report = client.reconcile().get(0).unwrap();
+    assert_eq!(report.keeper_fees, 10);
+    assert_eq!(report.balance, 1_000_000 - 1_000);
+    assert_eq!(report.surplus, 0);
+}
+
+#[test]
+#[should_panic(expected = "No surplus to sync")]
+fn sync_admin_balance_rejects_deficit() {
+    let env = Env::default();
+    let (client, minter) = setup(&env);
+    lose_tokens(&env, &client, &minter);
+    client.sync_admin_balance();
+}
@@ -0,0 +1,381 @@
+use soroban_sdk::{
+    contract, contractimpl, contracttype,
+    testutils::{Address as _, Ledger as _},
+    token, vec, Address, Env,
+};
+
+use vesting_contracts::{Milestone, StakingAdapter, VestingContractClient};
+
+mod common;
+
+// Staking mock that holds staked tokens and returns them to the vesting contract on withdraw
+#[contract]
+struct MockStaking;
+
+#[contracttype]
+enum MockDataKey {
+    Token,
+    Vesting,
+    Staked(u64),
+}
+
+#[contractimpl]
+impl MockStaking {
+    pub fn init(env: Env, token: Address, vesting: Address) {
+        env.storage().instance().set(&MockDataKey::Token, &token);
+        env.storage().instance().set(&MockDataKey::Vesting, &vesting);
+    }
+}
+
+#[contractimpl]
+impl StakingAdapter for MockStaking {
+    fn stake(env: Env, vault_id: u64, amount: i128, _validator: Address) {
+        let token: Address = env.storage().instance().get(&MockDataKey::Token).unwrap();
+        let held = token::Client::new(&env, &token).balance(&env.current_contract_address());
+        let staked: i128 = env
+            .storage()
+            .instance()
+            .get(&MockDataKey::Staked(vault_id))
+            .unwrap_or(0);
+        if held < staked + amount {
+            panic!("stake not funded");
+        }
+        env.storage()
+            .instance()
+            .set(&MockDataKey::Staked(vault_id), &(staked + amount));
+    }
+
+    fn unstake(env: Env, vault_id: u64, amount: i128) {
+        let staked: i128 = env
+            .storage()
+            .instance()
+            .get(&MockDataKey::Staked(vault_id))
+            .unwrap_or(0);
+        if amount > staked {
+            panic!("unstake exceeds stake");
+        }
+        env.storage()
+            .instance()
+            .set(&MockDataKey::Staked(vault_id), &(staked - amount));
+    }
+
+    fn withdraw(env: Env, _vault_id: u64, amount: i128) {
+        let token: Address = env.storage().instance().get(&MockDataKey::Token).unwrap();
+        let vesting: Address = env.storage().instance().get(&MockDataKey::Vesting).unwrap();
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &vesting,
+            &amount,
+        );
+    }
+
+    fn pending_rewards(_env: Env, _vault_id: u64) -> i128 {
+        0
+    }
+
+    fn claim_rewards(_env: Env, _vault_id: u64) -> i128 {
+        0
+    }
+}
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, Address, u64) {
+    let (client, _admin, token_addr) = common::setup(env);
+    let staking_id = env.register(MockStaking, ());
+    MockStakingClient::new(env, &staking_id).init(&token_addr, &client.address);
+    client.set_staking_contract(&staking_id);
+
+    let beneficiary = Address::generate(env);
+    let vault_id = common::create_vault(env, &client, &beneficiary, 0);
+
+    (client, staking_id, vault_id)
+}
+
+#[test]
+fn stake_and_unstake_track_validator() {
+    let env = Env::default();
+    let (client, _staking, vault_id) = setup(&env);
+    let validator = Address::generate(&env);
+
+    client.stake_tokens(&vault_id, &600i128, &validator);
+    assert_eq!(client.get_vault(&vault_id).staked_amount, 600);
+    assert_eq!(client.get_vault_validator(&vault_id), Some(validator.clone()));
+
+    client.unstake_tokens(&vault_id, &200i128);
+    assert_eq!(client.get_vault(&vault_id).staked_amount, 400);
+    assert_eq!(client.get_vault_validator(&vault_id), Some(validator));
+
+    client.unstake_tokens(&vault_id, &400i128);
+    assert_eq!(client.get_vault(&vault_id).staked_amount, 0);
+    assert_eq!(client.get_vault_validator(&vault_id), None);
+}
+
+#[test]
+#[should_panic(expected = "Vault is staked with a different validator")]
+fn stake_with_second_validator_panics() {
+    let env = Env::default();
+    let (client, _staking, vault_id) = setup(&env);
+
+    client.stake_tokens(&vault_id, &100i128, &Address::generate(&env));
+    client.stake_tokens(&vault_id, &100i128, &Address::generate(&env));
+}
+
+#[test]
+#[should_panic(expected = "Amount exceeds staked balance")]
+fn unstake_more_than_staked_panics() {
+    let env = Env::default();
+    let (client, _staking, vault_id) = setup(&env);
+
+    client.stake_tokens(&vault_id, &100i128, &Address::generate(&env));
+    client.unstake_tokens(&vault_id, &101i128);
+}
+
+#[test]
+fn reported_loss_reduces_vault_and_totals() {
+    let env = Env::default();
+    let (client, staking, vault_id) = setup(&env);
+
+    client.stake_tokens(&vault_id, &500i128, &Address::generate(&env));
+    client.report_stake_loss(&staking, &vault_id, &200i128);
+
+    let vault = client.get_vault(&vault_id);
+    assert_eq!(vault.staked_amount, 300);
+    assert_eq!(vault.total_amount, 800);
+
+    let (locked, _, _) = client.get_contract_state();
+    assert_eq!(locked, 800);
+    assert!(client.check_invariant());
+}
+
+#[test]
+fn reported_loss_is_shared_with_unbonding_requests() {
+    let env = Env::default();
+    let (client, staking, vault_id) = setup(&env);
+    client.set_unbonding_period(&100u64);
+
+    client.stake_tokens(&vault_id, &800i128, &Address::generate(&env));
+    client.unstake_tokens(&vault_id, &200i128);
+    client.unstake_tokens(&vault_id, &200i128);
+
+    // More than is still staked: 300 comes off the stake and 301 off the unbonding requests
+    client.report_stake_loss(&staking, &vault_id, &601i128);
+
+    let vault = client.get_vault(&vault_id);
+    assert_eq!(vault.staked_amount, 100);
+    assert_eq!(vault.total_amount, 399);
+    let requests = client.get_unbonding_requests(&vault_id);
+    assert_eq!(requests.get(0).unwrap().amount, 49);
+    assert_eq!(requests.get(1).unwrap().amount, 50);
+    assert!(client.check_invariant());
+
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    assert_eq!(client.complete_unbonding(&vault_id), 99);
+    assert!(client.check_invariant());
+}
+
+#[test]
+#[should_panic(expected = "Loss exceeds staked and unbonding amount")]
+fn loss_beyond_staked_and_unbonding_panics() {
+    let env = Env::default();
+    let (client, staking, vault_id) = setup(&env);
+    client.set_unbonding_period(&100u64);
+
+    client.stake_tokens(&vault_id, &500i128, &Address::generate(&env));
+    client.unstake_tokens(&vault_id, &200i128);
+    client.report_stake_loss(&staking, &vault_id, &501i128);
+}
+
+#[test]
+#[should_panic(expected = "Reporter is not staking contract or admin")]
+fn loss_report_from_stranger_panics() {
+    let env = Env::default();
+    let (client, _staking, vault_id) = setup(&env);
+
+    client.stake_tokens(&vault_id, &500i128, &Address::generate(&env));
+    client.report_stake_loss(&Address::generate(&env), &vault_id, &200i128);
+}
+
+#[test]
+fn unbonding_matures_before_tokens_become_liquid() {
+    let env = Env::default();
+    let (client, _staking, vault_id) = setup(&env);
+    client.set_unbonding_period(&100u64);
+
+    client.stake_tokens(&vault_id, &600i128, &Address::generate(&env));
+    client.unstake_tokens(&vault_id, &200i128);
+
+    let requests = client.get_unbonding_requests(&vault_id);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests.get(0).unwrap().amount, 200);
+
+    env.ledger().with_mut(|li| li.timestamp += 50);
+    assert_eq!(client.complete_unbonding(&vault_id), 0);
+
+    env.ledger().with_mut(|li| li.timestamp += 50);
+    assert_eq!(client.complete_unbonding(&vault_id), 200);
+    assert_eq!(client.get_unbonding_requests(&vault_id).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient liquid balance - tokens are staked or unbonding")]
+fn claim_cannot_draw_from_immature_unbonding() {
+    let env = Env::default();
+    let (client, _staking, vault_id) = setup(&env);
+    client.set_unbonding_period(&10_000u64);
+
+    client.stake_tokens(&vault_id, &800i128, &Address::generate(&env));
+    client.unstake_tokens(&vault_id, &800i128);
+
+    env.ledger().with_mut(|li| li.timestamp += 500);
+    client.claim_tokens(&vault_id, &300i128, &0i128);
+}
+
+#[test]
+fn staked_tokens_move_to_the_adapter_and_are_not_yield() {
+    let env = Env::default();
+    let (client, staking, vault_id) = setup(&env);
+    let token_addr = client.reconcile().get(0).unwrap().token;
+    let token = token::Client::new(&env, &token_addr);
+    client.set_unbonding_period(&100u64);
+
+    client.stake_tokens(&vault_id, &600i128, &Address::generate(&env));
+    assert_eq!(token.balance(&staking), 600);
+    assert_eq!(token.balance(&client.address), 1_000_000 - 600);
+
+    // Tokens at the adapter are still owed to the vault, so nothing is surplus
+    assert_eq!(client.sync_yield(), 0);
+    assert!(client.check_invariant());
+
+    client.unstake_tokens(&vault_id, &600i128);
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    assert_eq!(client.complete_unbonding(&vault_id), 600);
+    assert_eq!(token.balance(&staking), 0);
+    assert_eq!(token.balance(&client.address), 1_000_000);
+    assert_eq!(client.sync_yield(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient liquid balance - tokens are staked or unbonding")]
+fn delegate_claim_cannot_draw_from_immature_unbonding() {
+    let env = Env::default();
+    let (client, _staking, vault_id) = setup(&env);
+    client.set_unbonding_period(&10_000u64);
+    client.set_delegate(&vault_id, &Some(Address::generate(&env)));
+
+    client.stake_tokens(&vault_id, &800i128, &Address::generate(&env));
+    client.unstake_tokens(&vault_id, &800i128);
+
+    env.ledger().with_mut(|li| li.timestamp += 500);
+    client.claim_as_delegate(&vault_id, &300i128, &0i128);
+}
+
+#[test]
+fn auto_claim_skips_staked_or_unbonding_tokens() {
+    let env = Env::default();
+    let (client, _staking, vault_id) = setup(&env);
+    let keeper = Address::generate(&env);
+    client.set_unbonding_period(&10_000u64);
+
+    client.stake_tokens(&vault_id, &800i128, &Address::generate(&env));
+    client.unstake_tokens(&vault_id, &800i128);
+
+    // 500 vested, but only 200 is liquid
+    env.ledger().with_mut(|li| li.timestamp += 500);
+    assert!(client.try_auto_claim(&vault_id, &keeper).is_err());
+    let results = client.auto_claim_many(&vec![&env, vault_id], &keeper);
+    assert!(!results.get(0).unwrap().claimed);
+    assert_eq!(client.get_vault(&vault_id).released_amount, 0);
+}
+
+#[test]
+fn auto_claim_unstakes_shortfall_without_unbonding_period() {
+    let env = Env::default();
+    let (client, staking, vault_id) = setup(&env);
+    let token_addr = client.reconcile().get(0).unwrap().token;
+    let token = token::Client::new(&env, &token_addr);
+
+    client.stake_tokens(&vault_id, &800i128, &Address::generate(&env));
+
+    env.ledger().with_mut(|li| li.timestamp += 500);
+    client.auto_claim(&vault_id, &Address::generate(&env));
+
+    let vault = client.get_vault(&vault_id);
+    assert_eq!(vault.released_amount, 500);
+    assert_eq!(vault.staked_amount, 500);
+    assert_eq!(token.balance(&vault.owner), 500);
+    assert_eq!(token.balance(&staking), 500);
+    assert!(client.check_invariant());
+}
+
+#[test]
+fn revoke_returns_only_liquid_tokens() {
+    let env = Env::default();
+    let (client, _staking, vault_id) = setup(&env);
+    client.set_unbonding_period(&100u64);
+    client.stake_tokens(&vault_id, &800i128, &Address::generate(&env));
+
+    // Staked tokens stay with the vault until they are unbonded
+    assert_eq!(client.revoke_partial(&vault_id, &500i128), 200);
+    assert_eq!(client.get_vault(&vault_id).released_amount, 200);
+    assert!(client.try_revoke_tokens(&vault_id).is_err());
+    assert!(client.check_invariant());
+
+    client.unstake_tokens(&vault_id, &800i128);
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    assert_eq!(client.batch_revoke(&vec![&env, vault_id]), 800);
+
+    let vault = client.get_vault(&vault_id);
+    assert_eq!(vault.released_amount, 1_000);
+    assert_eq!(vault.staked_amount, 0);
+    assert!(client.check_invariant());
+}
+
+#[test]
+fn revoke_unstakes_without_unbonding_period() {
+    let env = Env::default();
+    let (client, staking, vault_id) = setup(&env);
+    let token_addr = client.reconcile().get(0).unwrap().token;
+    client.stake_tokens(&vault_id, &800i128, &Address::generate(&env));
+
+    assert_eq!(client.revoke_tokens(&vault_id), 1_000);
+    assert_eq!(client.get_vault(&vault_id).staked_amount, 0);
+    assert_eq!(token::Client::new(&env, &token_addr).balance(&staking), 0);
+    assert!(client.check_invariant());
+}
+
+#[test]
+#[should_panic(expected = "Insufficient liquid balance - tokens are staked or unbonding")]
+fn clawback_cannot_return_staked_tokens() {
+    let env = Env::default();
+    let (client, _staking, vault_id) = setup(&env);
+    client.set_unbonding_period(&100u64);
+    client.stake_tokens(&vault_id, &800i128, &Address::generate(&env));
+
+    client.clawback_vault(&vault_id);
+}
+
+#[test]
+fn expire_milestone_cannot_forfeit_staked_tokens() {
+    let env = Env::default();
+    let (client, _staking, vault_id) = setup(&env);
+    let now = env.ledger().timestamp();
+    client.set_unbonding_period(&100u64);
+    client.set_milestones(
+        &vault_id,
+        &vec![
+            &env,
+            Milestone {
+                deadline: now + 10,
+                ..common::milestone(1, 100)
+            },
+        ],
+    );
+    client.stake_tokens(&vault_id, &800i128, &Address::generate(&env));
+
+    env.ledger().with_mut(|li| li.timestamp = now + 11);
+    assert!(client.try_expire_milestone(&vault_id, &1u64).is_err());
+
+    client.unstake_tokens(&vault_id, &800i128);
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    assert_eq!(client.expire_milestone(&vault_id, &1u64), 1_000);
+    assert!(client.check_invariant());
+}
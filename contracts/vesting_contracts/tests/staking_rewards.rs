@@ -0,0 +1,133 @@
+use soroban_sdk::{
+    contract, contractimpl, contracttype,
+    testutils::{Address as _, Ledger as _},
+    token, Address, Env,
+};
+
+use vesting_contracts::{StakingAdapter, VestingContractClient};
+
+mod common;
+
+// Staking mock that pays preset rewards in the vesting token to the vesting contract
+#[contract]
+struct RewardingStaking;
+
+#[contracttype]
+enum MockDataKey {
+    Token,
+    Vesting,
+    Rewards(u64),
+}
+
+#[contractimpl]
+impl RewardingStaking {
+    pub fn init(env: Env, token: Address, vesting: Address) {
+        env.storage().instance().set(&MockDataKey::Token, &token);
+        env.storage().instance().set(&MockDataKey::Vesting, &vesting);
+    }
+
+    pub fn set_rewards(env: Env, vault_id: u64, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&MockDataKey::Rewards(vault_id), &amount);
+    }
+}
+
+#[contractimpl]
+impl StakingAdapter for RewardingStaking {
+    fn stake(_env: Env, _vault_id: u64, _amount: i128, _validator: Address) {}
+
+    fn unstake(_env: Env, _vault_id: u64, _amount: i128) {}
+
+    fn withdraw(_env: Env, _vault_id: u64, _amount: i128) {}
+
+    fn pending_rewards(env: Env, vault_id: u64) -> i128 {
+        env.storage()
+            .instance()
+            .get(&MockDataKey::Rewards(vault_id))
+            .unwrap_or(0)
+    }
+
+    fn claim_rewards(env: Env, vault_id: u64) -> i128 {
+        let amount = Self::pending_rewards(env.clone(), vault_id);
+        if amount > 0 {
+            let token: Address = env.storage().instance().get(&MockDataKey::Token).unwrap();
+            let vesting: Address = env.storage().instance().get(&MockDataKey::Vesting).unwrap();
+            token::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &vesting,
+                &amount,
+            );
+            env.storage()
+                .instance()
+                .set(&MockDataKey::Rewards(vault_id), &0i128);
+        }
+        amount
+    }
+}
+
+struct Setup {
+    client: VestingContractClient<'static>,
+    staking: RewardingStakingClient<'static>,
+    token: token::Client<'static>,
+    beneficiary: Address,
+    vault_id: u64,
+}
+
+fn setup(env: &Env) -> Setup {
+    let (client, _admin, token_addr) = common::setup(env);
+    let minter = token::StellarAssetClient::new(env, &token_addr);
+
+    let staking_id = env.register(RewardingStaking, ());
+    let staking = RewardingStakingClient::new(env, &staking_id);
+    staking.init(&token_addr, &client.address);
+    minter.mint(&staking_id, &10_000i128);
+    client.set_staking_contract(&staking_id);
+
+    let beneficiary = Address::generate(env);
+    let vault_id = common::create_vault(env, &client, &beneficiary, 0);
+
+    Setup {
+        client,
+        staking,
+        token: token::Client::new(env, &token_addr),
+        beneficiary,
+        vault_id,
+    }
+}
+
+#[test]
+fn harvested_rewards_are_claimable_and_excluded_from_yield() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.staking.set_rewards(&s.vault_id, &300i128);
+    assert_eq!(s.client.harvest_staking_rewards(&s.vault_id), 300);
+    assert_eq!(s.client.get_staking_rewards(&s.vault_id).claimable, 300);
+
+    // Reward tokens must not inflate the share-to-token rate of regular claims
+    env.ledger().with_mut(|li| li.timestamp += 1_000);
+    s.client.claim_tokens(&s.vault_id, &1_000i128, &0i128);
+    assert_eq!(s.token.balance(&s.beneficiary), 1_000);
+
+    assert_eq!(s.client.claim_staking_rewards(&s.vault_id), 300);
+    assert_eq!(s.token.balance(&s.beneficiary), 1_300);
+    assert!(s.client.check_invariant());
+}
+
+#[test]
+fn compounded_rewards_vest_with_vault_schedule() {
+    let env = Env::default();
+    let s = setup(&env);
+
+    s.client.set_reward_compounding(&s.vault_id, &true);
+    s.staking.set_rewards(&s.vault_id, &400i128);
+    s.client.harvest_staking_rewards(&s.vault_id);
+    assert_eq!(s.client.get_staking_rewards(&s.vault_id).compounded, 400);
+
+    env.ledger().with_mut(|li| li.timestamp += 250);
+    assert_eq!(s.client.claim_staking_rewards(&s.vault_id), 100);
+
+    env.ledger().with_mut(|li| li.timestamp += 750);
+    assert_eq!(s.client.claim_staking_rewards(&s.vault_id), 300);
+}
@@ -0,0 +1,125 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Vec};
+
+use vesting_contracts::{
+    BatchCreateData, DataKey, MilestoneV0, VestingContractClient, MIGRATION_CHUNK_SIZE,
+    SCHEMA_VERSION,
+};
+
+mod common;
+
+// Built by `make build` (stellar contract build) or the CI workflow before the tests run
+const VESTING_WASM: &[u8] =
+    include_bytes!("../../../target/wasm32v1-none/release/vesting_contracts.wasm");
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, Address) {
+    env.mock_all_auths();
+    // The unoptimized test wasm exceeds the default budget and mainnet code size limit
+    env.cost_estimate().budget().reset_unlimited();
+    env.cost_estimate().disable_resource_limits();
+
+    let (client, admin, _token) = common::setup(env);
+    (client, admin)
+}
+
+#[test]
+fn admin_can_upgrade_without_opting_in() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    assert_eq!(client.get_upgrade_authority(), None);
+
+    let wasm_hash = env.deployer().upload_contract_wasm(VESTING_WASM);
+    client.upgrade(&admin, &wasm_hash);
+
+    // State survives the code swap
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_schema_version(), SCHEMA_VERSION);
+}
+
+#[test]
+fn migrate_storage_runs_in_chunks() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let count = MIGRATION_CHUNK_SIZE as u32 + 10;
+    let mut batch = BatchCreateData {
+        recipients: Vec::new(&env),
+        amounts: Vec::new(&env),
+        start_times: Vec::new(&env),
+        end_times: Vec::new(&env),
+        keeper_fees: Vec::new(&env),
+        step_durations: Vec::new(&env),
+    };
+    let now = env.ledger().timestamp();
+    for _ in 0..count {
+        batch.recipients.push_back(Address::generate(&env));
+        batch.amounts.push_back(100);
+        batch.start_times.push_back(now);
+        batch.end_times.push_back(now + 1_000);
+        batch.keeper_fees.push_back(0);
+        batch.step_durations.push_back(0);
+    }
+    client.batch_create_vaults_full(&batch);
+
+    // Simulate a deployment from before schema versioning
+    env.as_contract(&client.address, || {
+        env.storage().instance().remove(&DataKey::SchemaVersion);
+    });
+    assert_eq!(client.get_schema_version(), 0);
+
+    assert!(!client.migrate_storage(&0u32));
+    assert_eq!(client.get_schema_version(), 0);
+    assert!(client.migrate_storage(&0u32));
+    assert_eq!(client.get_schema_version(), 1);
+
+    assert!(!client.migrate_storage(&1u32));
+    assert!(client.migrate_storage(&1u32));
+    assert_eq!(client.get_schema_version(), SCHEMA_VERSION);
+    assert_eq!(client.get_vault(&(count as u64)).total_amount, 100);
+}
+
+#[test]
+fn migrate_storage_rewrites_v0_milestones() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+
+    let vault_id = common::create_vault(&env, &client, &Address::generate(&env), 0);
+    let legacy = vec![
+        &env,
+        MilestoneV0 {
+            id: 1,
+            percentage: 40,
+            is_unlocked: true,
+        },
+        MilestoneV0 {
+            id: 2,
+            percentage: 60,
+            is_unlocked: false,
+        },
+    ];
+    env.as_contract(&client.address, || {
+        let storage = env.storage().instance();
+        storage.remove(&DataKey::SchemaVersion);
+        storage.set(&DataKey::VaultMilestones(vault_id), &legacy);
+    });
+
+    while !client.migrate_storage(&0u32) {}
+
+    let milestones = client.get_milestones(&vault_id);
+    assert_eq!(milestones.len(), 2);
+    let first = milestones.get(0).unwrap();
+    assert!(first.is_unlocked);
+    assert_eq!(first.percentage, 40);
+    assert_eq!(first.challenge_ends_at, 0);
+    assert!(!milestones.get(1).unwrap().is_unlocked);
+
+    // The tranche unlocked before the upgrade is still claimable in full
+    assert_eq!(client.claim_tokens(&vault_id, &400i128, &0i128), 400);
+}
+
+#[test]
+#[should_panic(expected = "Storage already at current schema")]
+fn migrate_storage_rejects_current_schema() {
+    let env = Env::default();
+    let (client, _admin) = setup(&env);
+    client.migrate_storage(&SCHEMA_VERSION);
+}
@@ -0,0 +1,91 @@
+use soroban_sdk::{testutils::Address as _, Address, Env, String};
+
+use vesting_contracts::{DataKey, Vault, VaultV1, VersionedVault, VestingContractClient};
+
+mod common;
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, u64) {
+    let (client, _admin, _token_addr) = common::setup(env);
+
+    let vault_id = common::create_vault(env, &client, &Address::generate(env), 0);
+
+    (client, vault_id)
+}
+
+fn stored_version(env: &Env, client: &VestingContractClient, vault_id: u64) -> VersionedVault {
+    env.as_contract(&client.address, || {
+        env.storage()
+            .instance()
+            .get(&DataKey::VaultData(vault_id))
+            .unwrap()
+    })
+}
+
+#[test]
+fn bare_v1_record_is_upgraded_on_read_and_rewritten_on_mutation() {
+    let env = Env::default();
+    let (client, vault_id) = setup(&env);
+    let current = client.get_vault(&vault_id);
+
+    let v1 = VaultV1 {
+        owner: current.owner.clone(),
+        total_amount: current.total_amount,
+        released_amount: current.released_amount,
+        start_time: current.start_time,
+        end_time: current.end_time,
+        keeper_fee: current.keeper_fee,
+        is_initialized: current.is_initialized,
+        is_irrevocable: current.is_irrevocable,
+        creation_time: current.creation_time,
+        is_transferable: current.is_transferable,
+        step_duration: current.step_duration,
+    };
+    // Deployments that predate VersionedVault stored bare VaultV1 structs
+    env.as_contract(&client.address, || {
+        env.storage()
+            .instance()
+            .set::<DataKey, VaultV1>(&DataKey::VaultData(vault_id), &v1);
+    });
+
+    let vault = client.get_vault(&vault_id);
+    assert_eq!(vault.owner, current.owner);
+    assert_eq!(vault.total_amount, 1_000);
+    assert_eq!(vault.title, String::from_str(&env, ""));
+    assert_eq!(vault.delegate, None);
+    assert_eq!(vault.staked_amount, 0);
+    assert!(!vault.is_frozen);
+
+    // Reads leave the record alone
+    env.as_contract(&client.address, || {
+        let raw: VaultV1 = env
+            .storage()
+            .instance()
+            .get(&DataKey::VaultData(vault_id))
+            .unwrap();
+        assert_eq!(raw.total_amount, 1_000);
+    });
+
+    client.freeze_vault(&vault_id);
+    match stored_version(&env, &client, vault_id) {
+        VersionedVault::V2(vault) => assert!(vault.is_frozen),
+        VersionedVault::V1(_) => panic!("vault was not rewritten as V2"),
+    }
+}
+
+#[test]
+fn unversioned_record_is_still_readable() {
+    let env = Env::default();
+    let (client, vault_id) = setup(&env);
+    let current = client.get_vault(&vault_id);
+
+    // Records written before VersionedVault existed are bare Vault structs
+    env.as_contract(&client.address, || {
+        env.storage()
+            .instance()
+            .set::<DataKey, Vault>(&DataKey::VaultData(vault_id), &current);
+    });
+
+    assert_eq!(client.get_vault(&vault_id).total_amount, current.total_amount);
+    client.freeze_vault(&vault_id);
+    assert!(matches!(stored_version(&env, &client, vault_id), VersionedVault::V2(_)));
+}
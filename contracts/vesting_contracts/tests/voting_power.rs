@@ -0,0 +1,106 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    Address, Env,
+};
+
+use vesting_contracts::VestingContractClient;
+
+mod common;
+
+fn setup(env: &Env) -> VestingContractClient<'static> {
+    let (client, _admin, _token_addr) = common::setup(env);
+
+    client
+}
+
+fn create_vault(
+    env: &Env,
+    client: &VestingContractClient,
+    owner: &Address,
+    transferable: bool,
+) -> u64 {
+    let now = env.ledger().timestamp();
+    client.create_vault_full(
+        owner,
+        &1_000i128,
+        &now,
+        &(now + 1_000),
+        &0i128,
+        &true,
+        &transferable,
+        &0u64,
+    )
+}
+
+#[test]
+fn votes_track_unvested_balance_with_history() {
+    let env = Env::default();
+    let client = setup(&env);
+    let alice = Address::generate(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    let vault_id = create_vault(&env, &client, &alice, false);
+    assert_eq!(client.get_votes(&alice), 1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 600);
+    client.claim_tokens(&vault_id, &500i128, &0i128);
+    assert_eq!(client.get_votes(&alice), 500);
+
+    env.ledger().with_mut(|li| li.timestamp = 700);
+    assert_eq!(client.get_past_votes(&alice, &99), 0);
+    assert_eq!(client.get_past_votes(&alice, &100), 1_000);
+    assert_eq!(client.get_past_votes(&alice, &599), 1_000);
+    assert_eq!(client.get_past_votes(&alice, &600), 500);
+
+    client.revoke_tokens(&vault_id);
+    assert_eq!(client.get_votes(&alice), 0);
+}
+
+#[test]
+fn votes_follow_vault_transfer() {
+    let env = Env::default();
+    let client = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let vault_id = create_vault(&env, &client, &alice, true);
+    client.transfer_vault(&vault_id, &bob);
+
+    assert_eq!(client.get_votes(&alice), 0);
+    assert_eq!(client.get_votes(&bob), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Timestamp not yet finalized")]
+fn past_votes_for_current_timestamp_panics() {
+    let env = Env::default();
+    let client = setup(&env);
+
+    client.get_past_votes(&Address::generate(&env), &env.ledger().timestamp());
+}
+
+#[test]
+fn delegated_votes_move_to_delegatee_and_follow_claims() {
+    let env = Env::default();
+    let client = setup(&env);
+    let alice = Address::generate(&env);
+    let carol = Address::generate(&env);
+
+    let vault_id = create_vault(&env, &client, &alice, false);
+    client.delegate_votes(&alice, &carol);
+
+    assert_eq!(client.get_vote_delegate(&alice), carol);
+    assert_eq!(client.get_votes(&alice), 0);
+    assert_eq!(client.get_votes(&carol), 1_000);
+
+    env.ledger().with_mut(|li| li.timestamp += 400);
+    client.claim_tokens(&vault_id, &400i128, &0i128);
+    assert_eq!(client.get_votes(&carol), 600);
+
+    // Vault is untouched by vote delegation
+    assert_eq!(client.get_vault(&vault_id).delegate, None);
+
+    client.delegate_votes(&alice, &alice);
+    assert_eq!(client.get_votes(&alice), 600);
+    assert_eq!(client.get_votes(&carol), 0);
+}
@@ -0,0 +1,113 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Map};
+
+use vesting_contracts::{DataKey, VestingContractClient, WhitelistDataKey};
+
+mod common;
+
+fn setup(env: &Env) -> (VestingContractClient<'static>, Address, Address) {
+    let (client, admin, token_addr) = common::setup(env);
+
+    (client, admin, token_addr)
+}
+
+fn create_vault(env: &Env, client: &VestingContractClient, amount: i128) -> u64 {
+    let now = env.ledger().timestamp();
+    client.create_vault_full(
+        &Address::generate(env),
+        &amount,
+        &now,
+        &(now + 1_000),
+        &0i128,
+        &true,
+        &false,
+        &0u64,
+    )
+}
+
+#[test]
+fn whitelist_can_be_listed_and_pruned() {
+    let env = Env::default();
+    let (client, admin, token_addr) = setup(&env);
+    assert_eq!(
+        client.get_whitelisted_tokens(),
+        vec![&env, token_addr.clone()]
+    );
+
+    let other = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    client.add_to_whitelist(&other, &5_000i128);
+    assert_eq!(
+        client.get_whitelisted_tokens(),
+        vec![&env, token_addr.clone(), other.clone()]
+    );
+
+    let metadata = client.get_token_metadata(&other).unwrap();
+    assert_eq!(metadata.decimals, 7);
+    assert_eq!(metadata.locked_cap, 5_000);
+
+    client.remove_from_whitelist(&other);
+    assert_eq!(client.get_whitelisted_tokens(), vec![&env, token_addr]);
+    assert_eq!(client.get_token_metadata(&other), None);
+}
+
+#[test]
+#[should_panic(expected = "Token locked_cap exceeded")]
+fn creation_respects_token_cap() {
+    let env = Env::default();
+    let (client, _admin, token_addr) = setup(&env);
+
+    client.add_to_whitelist(&token_addr, &1_500i128);
+    create_vault(&env, &client, 1_000);
+    create_vault(&env, &client, 600);
+}
+
+#[test]
+#[should_panic(expected = "Token is not whitelisted")]
+fn creation_rejects_removed_vesting_token() {
+    let env = Env::default();
+    let (client, _admin, token_addr) = setup(&env);
+
+    client.remove_from_whitelist(&token_addr);
+    create_vault(&env, &client, 1_000);
+}
+
+#[test]
+fn migration_moves_legacy_whitelist_map() {
+    let env = Env::default();
+    let (client, admin, token_addr) = setup(&env);
+    let rescue = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let dropped = env.register_stellar_asset_contract_v2(admin).address();
+
+    // Pre-series deployments kept a Map<Address, bool> without metadata or the vesting token
+    env.as_contract(&client.address, || {
+        let storage = env.storage().instance();
+        storage.remove(&DataKey::SchemaVersion);
+        storage.remove(&WhitelistDataKey::TokenList);
+        storage.remove(&WhitelistDataKey::TokenMetadata(token_addr.clone()));
+        let mut legacy: Map<Address, bool> = Map::new(&env);
+        legacy.set(rescue.clone(), true);
+        legacy.set(dropped.clone(), false);
+        storage.set(&WhitelistDataKey::WhitelistedTokens, &legacy);
+    });
+
+    while !client.migrate_storage(&0u32) {}
+
+    let tokens = client.get_whitelisted_tokens();
+    assert_eq!(tokens.len(), 2);
+    assert!(tokens.contains(&rescue));
+    assert!(tokens.contains(&token_addr));
+    assert_eq!(client.get_token_metadata(&rescue).unwrap().decimals, 7);
+    assert_eq!(client.get_token_metadata(&dropped), None);
+    env.as_contract(&client.address, || {
+        assert!(!env
+            .storage()
+            .instance()
+            .has(&WhitelistDataKey::WhitelistedTokens));
+    });
+
+    // The vesting token is usable again without an admin re-whitelisting it
+    create_vault(&env, &client, 1_000);
+}
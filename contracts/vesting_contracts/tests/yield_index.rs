@@ -0,0 +1,96 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    token, Address, Env,
+};
+
+use vesting_contracts::VestingContractClient;
+
+mod common;
+
+struct Setup {
+    client: VestingContractClient<'static>,
+    token: token::Client<'static>,
+    minter: token::StellarAssetClient<'static>,
+}
+
+fn setup(env: &Env) -> Setup {
+    let (client, _admin, token_addr) = common::setup(env);
+    let minter = token::StellarAssetClient::new(env, &token_addr);
+
+    Setup {
+        client,
+        token: token::Client::new(env, &token_addr),
+        minter,
+    }
+}
+
+fn create_vault(env: &Env, client: &VestingContractClient, owner: &Address, amount: i128) -> u64 {
+    let now = env.ledger().timestamp();
+    client.create_vault_full(
+        owner,
+        &amount,
+        &now,
+        &(now + 1_000),
+        &0i128,
+        &true,
+        &false,
+        &0u64,
+    )
+}
+
+#[test]
+fn synced_yield_accrues_pro_rata_and_is_order_independent() {
+    let env = Env::default();
+    let s = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let a = create_vault(&env, &s.client, &alice, 1_000);
+    let b = create_vault(&env, &s.client, &bob, 3_000);
+
+    s.minter.mint(&s.client.address, &400i128);
+    assert_eq!(s.client.sync_yield(), 400);
+    assert_eq!(s.client.get_accrued_yield(&a), 100);
+    assert_eq!(s.client.get_accrued_yield(&b), 300);
+
+    env.ledger().with_mut(|li| li.timestamp += 1_000);
+    s.client.claim_tokens(&b, &3_000i128, &0i128);
+    s.client.claim_tokens(&a, &1_000i128, &0i128);
+
+    assert_eq!(s.token.balance(&alice), 1_100);
+    assert_eq!(s.token.balance(&bob), 3_300);
+    assert_eq!(s.client.get_accrued_yield(&a), 0);
+}
+
+#[test]
+fn unsynced_donation_does_not_change_payouts() {
+    let env = Env::default();
+    let s = setup(&env);
+    let alice = Address::generate(&env);
+
+    let a = create_vault(&env, &s.client, &alice, 1_000);
+    s.minter.mint(&s.client.address, &500i128);
+
+    env.ledger().with_mut(|li| li.timestamp += 1_000);
+    s.client.claim_tokens(&a, &1_000i128, &0i128);
+    assert_eq!(s.token.balance(&alice), 1_000);
+}
+
+#[test]
+fn vault_created_after_sync_does_not_share_earlier_yield() {
+    let env = Env::default();
+    let s = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let a = create_vault(&env, &s.client, &alice, 1_000);
+    s.minter.mint(&s.client.address, &100i128);
+    s.client.sync_yield();
+
+    let b = create_vault(&env, &s.client, &bob, 1_000);
+    assert_eq!(s.client.get_accrued_yield(&a), 100);
+    assert_eq!(s.client.get_accrued_yield(&b), 0);
+
+    assert_eq!(s.client.claim_yield(&a), 100);
+    assert_eq!(s.token.balance(&alice), 100);
+}